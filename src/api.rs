@@ -1,97 +1,216 @@
+use crate::config::ApiConfig;
+use crate::http::HttpClient;
 use crate::models::*;
-use reqwest::Client as ReqwestClient;
-use std::error::Error;
-
-// Function to get user balance from the API
-pub async fn get_user_balance(
-    client: &ReqwestClient,
-    base_url: &str,
-    user_id: i32,
-) -> Result<UserBalance, Box<dyn Error + Send + Sync>> {
-    let url = format!("{}/get-user-balance/{}", base_url, user_id);
-    let response = client.get(&url).send().await?.json::<UserBalance>().await?;
-
-    Ok(response)
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, USER_AGENT};
+use reqwest::{Client as ReqwestClient, StatusCode};
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::time::{Duration, Instant};
+use tracing::{debug, field, info_span, Instrument};
+
+// How much of an undecodable response body to keep for error messages
+const BODY_SNIPPET_LEN: usize = 200;
+
+// Errors that can occur when calling the StackUp API
+#[derive(Debug)]
+pub enum StackUpApiError {
+    // The requested resource (usually a user) doesn't exist
+    NotFound,
+    // The API rejected our credentials
+    Unauthorized,
+    // Too many requests, optionally with how long to wait before retrying
+    RateLimited { retry_after: Option<Duration> },
+    // Any other unsuccessful HTTP status
+    Http(StatusCode),
+    // The response body didn't match the expected shape
+    Decode { message: String, body: String },
+    // The request never got a response (connection failure, timeout, ...)
+    Network(reqwest::Error),
+    // StackUp has been failing, so calls are paused for a while instead of being sent
+    Unavailable { retry_in: Duration },
+}
+
+impl fmt::Display for StackUpApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StackUpApiError::NotFound => write!(f, "resource not found"),
+            StackUpApiError::Unauthorized => write!(f, "unauthorized"),
+            StackUpApiError::RateLimited {
+                retry_after: Some(retry_after),
+            } => write!(f, "rate limited, retry after {}s", retry_after.as_secs()),
+            StackUpApiError::RateLimited { retry_after: None } => write!(f, "rate limited"),
+            StackUpApiError::Http(status) => write!(f, "unexpected HTTP status {}", status),
+            StackUpApiError::Decode { message, body } => {
+                write!(f, "failed to decode response: {} (body: {})", message, body)
+            }
+            StackUpApiError::Network(err) => write!(f, "network error: {}", err),
+            StackUpApiError::Unavailable { retry_in } => {
+                write!(f, "circuit breaker open, retry in {}s", retry_in.as_secs())
+            }
+        }
+    }
+}
+
+impl StackUpApiError {
+    // Get a short explanation of the error that is safe to show to Discord users
+    pub fn reason(&self) -> String {
+        match self {
+            // Also seen when looking someone else up, so it can't point at the caller's `/link`
+            StackUpApiError::NotFound => {
+                "StackUp couldn't find that account. It may no longer exist.".to_string()
+            }
+            StackUpApiError::Unauthorized => {
+                "Stack-Buddy isn't allowed to access StackUp right now.".to_string()
+            }
+            StackUpApiError::RateLimited {
+                retry_after: Some(retry_after),
+            } => format!(
+                "StackUp is busy right now. Please try again in {}s.",
+                retry_after.as_secs()
+            ),
+            StackUpApiError::RateLimited { retry_after: None } => {
+                "StackUp is busy right now. Please try again in a moment.".to_string()
+            }
+            StackUpApiError::Http(status) => format!(
+                "StackUp returned an unexpected error (HTTP {}). Please try again later.",
+                status.as_u16()
+            ),
+            StackUpApiError::Decode { .. } => {
+                "StackUp sent a response Stack-Buddy couldn't understand.".to_string()
+            }
+            StackUpApiError::Network(_) => {
+                "Stack-Buddy couldn't reach StackUp. Please try again later.".to_string()
+            }
+            StackUpApiError::Unavailable { retry_in } => format!(
+                "StackUp seems to be having trouble right now, so Stack-Buddy is giving it a short break. Please try again in about {}s.",
+                retry_in.as_secs().max(1)
+            ),
+        }
+    }
 }
 
-// Function to get user profile from the API
-pub async fn get_user_progress(
-    client: &ReqwestClient,
-    base_url: &str,
-    user_id: i32,
-) -> Result<UserProgress, Box<dyn Error + Send + Sync>> {
-    let url = format!("{}/get-user-progress/{}", base_url, user_id);
-    let response = client
-        .get(&url)
-        .send()
-        .await?
-        .json::<UserProgress>()
-        .await?;
-
-    Ok(response)
+impl std::error::Error for StackUpApiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StackUpApiError::Network(err) => Some(err),
+            _ => None,
+        }
+    }
 }
 
-// Function to get user details from the API
-pub async fn get_user_details(
-    client: &ReqwestClient,
-    base_url: &str,
-    user_id: i32,
-) -> Result<GetUserResponse, reqwest::Error> {
-    let url = format!("{}/get-user/{}", base_url, user_id);
-    let response = client
-        .get(&url)
-        .send()
-        .await?
-        .json::<GetUserResponse>()
-        .await?;
-
-    Ok(response)
+impl From<reqwest::Error> for StackUpApiError {
+    fn from(err: reqwest::Error) -> Self {
+        StackUpApiError::Network(err)
+    }
 }
 
-// Function to get featured campaigns from the API
-pub async fn get_featured_campaigns(
-    client: &ReqwestClient,
-    base_url: &str,
-) -> Result<Vec<Campaigns>, Box<dyn Error + Send + Sync>> {
-    let url = format!("{}/stack-featured-campaigns", base_url);
-    let response = client
-        .get(&url)
-        .send()
-        .await?
-        .json::<Vec<Campaigns>>()
-        .await?;
-
-    Ok(response)
+// The StackUp API endpoints the bot uses. `StackUpClient` calls the real API; the cache and
+// tests wrap or replace it with their own implementations.
+#[async_trait]
+pub trait StackUpApi: Send + Sync {
+    // Get a user's balance
+    async fn get_user_balance(&self, user_id: i32) -> Result<UserBalance, StackUpApiError>;
+
+    // Get a user's quest progress, shown as their profile
+    async fn get_user_progress(&self, user_id: i32) -> Result<UserProgress, StackUpApiError>;
+
+    // Get a user's details
+    async fn get_user_details(&self, user_id: i32) -> Result<GetUserResponse, StackUpApiError>;
+
+    // Get the featured campaigns
+    async fn get_featured_campaigns(&self) -> Result<Vec<Campaigns>, StackUpApiError>;
+
+    // Get the featured pathways
+    async fn get_pathways(&self) -> Result<Vec<Pathways>, StackUpApiError>;
+
+    // Get the featured hackathons
+    async fn get_hackathons(&self) -> Result<Vec<Hackathons>, StackUpApiError>;
 }
 
-// Function to get pathways from the API
-pub async fn get_pathways(
-    client: &ReqwestClient,
-    base_url: &str,
-) -> Result<Vec<Pathways>, Box<dyn Error + Send + Sync>> {
-    let url = format!("{}/stack-featured-pathways", base_url);
-    let response = client
-        .get(&url)
-        .send()
-        .await?
-        .json::<Vec<Pathways>>()
-        .await?;
-
-    Ok(response)
+// A client for the StackUp API, owning the HTTP client (with its retries and circuit breaker),
+// the base URL and the headers sent with every request
+pub struct StackUpClient {
+    http: HttpClient,
+    base_url: String,
 }
 
-// Function to get hackathons from the API
-pub async fn get_hackathons(
-    client: &ReqwestClient,
-    base_url: &str,
-) -> Result<Vec<Hackathons>, Box<dyn Error + Send + Sync>> {
-    let url = format!("{}/stack-featured-hackathons", base_url);
-    let response = client
-        .get(&url)
-        .send()
-        .await?
-        .json::<Vec<Hackathons>>()
-        .await?;
-
-    Ok(response)
+impl StackUpClient {
+    // Create a client with the base URL, credentials, timeouts and retries from the config
+    pub fn new(config: &ApiConfig) -> reqwest::Result<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            USER_AGENT,
+            HeaderValue::from_static(concat!("Stack-Buddy/", env!("CARGO_PKG_VERSION"))),
+        );
+        if let Some(auth_token) = &config.auth_token {
+            headers.insert(AUTHORIZATION, auth_token.clone());
+        }
+
+        let client = ReqwestClient::builder()
+            .default_headers(headers)
+            .connect_timeout(config.connect_timeout)
+            .timeout(config.request_timeout)
+            .build()?;
+
+        Ok(Self {
+            http: HttpClient::new(client, config),
+            base_url: config.base_url.clone(),
+        })
+    }
+
+    // Send a GET request for a path under the base URL and decode the JSON response, in a span
+    // of its own under the command that made it
+    async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T, StackUpApiError> {
+        let span = info_span!("stackup_request", path, latency_ms = field::Empty);
+        let started = Instant::now();
+        let result = self.fetch_json(path).instrument(span.clone()).await;
+        span.record("latency_ms", started.elapsed().as_millis() as u64);
+        span.in_scope(|| match &result {
+            Ok(_) => debug!("StackUp request succeeded"),
+            Err(err) => debug!(error = %err, "StackUp request failed"),
+        });
+        result
+    }
+
+    async fn fetch_json<T: DeserializeOwned>(&self, path: &str) -> Result<T, StackUpApiError> {
+        let url = format!("{}/{}", self.base_url, path);
+        let response = self.http.get(&url).await?;
+
+        // Decode the body, keeping a snippet of it if it doesn't match the expected shape
+        let body = response.text().await?;
+        serde_json::from_str(&body).map_err(|err| StackUpApiError::Decode {
+            message: err.to_string(),
+            body: body.chars().take(BODY_SNIPPET_LEN).collect(),
+        })
+    }
+}
+
+#[async_trait]
+impl StackUpApi for StackUpClient {
+    async fn get_user_balance(&self, user_id: i32) -> Result<UserBalance, StackUpApiError> {
+        self.get_json(&format!("get-user-balance/{}", user_id))
+            .await
+    }
+
+    async fn get_user_progress(&self, user_id: i32) -> Result<UserProgress, StackUpApiError> {
+        self.get_json(&format!("get-user-progress/{}", user_id))
+            .await
+    }
+
+    async fn get_user_details(&self, user_id: i32) -> Result<GetUserResponse, StackUpApiError> {
+        self.get_json(&format!("get-user/{}", user_id)).await
+    }
+
+    async fn get_featured_campaigns(&self) -> Result<Vec<Campaigns>, StackUpApiError> {
+        self.get_json("stack-featured-campaigns").await
+    }
+
+    async fn get_pathways(&self) -> Result<Vec<Pathways>, StackUpApiError> {
+        self.get_json("stack-featured-pathways").await
+    }
+
+    async fn get_hackathons(&self) -> Result<Vec<Hackathons>, StackUpApiError> {
+        self.get_json("stack-featured-hackathons").await
+    }
 }
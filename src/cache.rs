@@ -0,0 +1,271 @@
+use crate::api::{StackUpApi, StackUpApiError};
+use crate::config::CacheConfig;
+use crate::models::*;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{warn, Instrument};
+
+// A cached response and when it was fetched
+struct Entry<V> {
+    value: V,
+    fetched_at: Instant,
+    // Whether a background refresh of this entry is already running
+    refreshing: bool,
+}
+
+// How often a cache has been used, shown by `!cache-stats`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CacheStats {
+    pub entries: usize,
+    // Served from a fresh entry
+    pub hits: u64,
+    // Served from a stale entry while it was refreshed in the background
+    pub stale_hits: u64,
+    // Fetched from the API while the caller waited
+    pub misses: u64,
+}
+
+struct State<K, V> {
+    entries: HashMap<K, Entry<V>>,
+    stats: CacheStats,
+}
+
+// An in-memory cache for one API endpoint.
+// Entries are fresh for `ttl`; for `stale` after that they are still served, but trigger a
+// background refresh so the next caller gets new data. Older entries are fetched again.
+pub struct TtlCache<K, V> {
+    ttl: Duration,
+    stale: Duration,
+    state: Mutex<State<K, V>>,
+}
+
+impl<K, V> TtlCache<K, V>
+where
+    K: Eq + Hash + Clone + Send + 'static,
+    V: Clone + Send + 'static,
+{
+    pub fn new(ttl: Duration, stale: Duration) -> Self {
+        Self {
+            ttl,
+            stale,
+            state: Mutex::new(State {
+                entries: HashMap::new(),
+                stats: CacheStats::default(),
+            }),
+        }
+    }
+
+    // Get the cached value for a key, calling `fetch` when there is none or it has expired.
+    // Failed fetches are never cached, and a failed background refresh keeps the stale value.
+    pub async fn get_or_fetch<F, Fut>(
+        self: &Arc<Self>,
+        key: K,
+        fetch: F,
+    ) -> Result<V, StackUpApiError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, StackUpApiError>> + Send + 'static,
+    {
+        // A TTL of zero turns the cache off for this endpoint
+        if self.ttl.is_zero() {
+            return fetch().await;
+        }
+
+        let refresh = {
+            let mut state = self.state.lock().unwrap();
+            let State { entries, stats } = &mut *state;
+            match entries.get_mut(&key) {
+                Some(entry) if entry.fetched_at.elapsed() < self.ttl => {
+                    stats.hits += 1;
+                    return Ok(entry.value.clone());
+                }
+                Some(entry) if entry.fetched_at.elapsed() < self.ttl + self.stale => {
+                    stats.stale_hits += 1;
+                    let value = entry.value.clone();
+                    if entry.refreshing {
+                        return Ok(value);
+                    }
+                    entry.refreshing = true;
+                    Some(value)
+                }
+                _ => {
+                    stats.misses += 1;
+                    None
+                }
+            }
+        };
+
+        match refresh {
+            // Serve the stale value straight away and refresh it in the background
+            Some(value) => {
+                let cache = Arc::clone(self);
+                let request = fetch();
+                tokio::spawn(
+                    async move {
+                        let result = request.await;
+                        let mut state = cache.state.lock().unwrap();
+                        match result {
+                            Ok(value) => cache.insert(&mut state, key, value),
+                            Err(why) => {
+                                warn!(error = ?why, "Error refreshing cached response");
+                                if let Some(entry) = state.entries.get_mut(&key) {
+                                    entry.refreshing = false;
+                                }
+                            }
+                        }
+                    }
+                    // Keep the refresh's logs and StackUp call under the command that set it off
+                    .in_current_span(),
+                );
+                Ok(value)
+            }
+            None => {
+                let value = fetch().await?;
+                let mut state = self.state.lock().unwrap();
+                self.insert(&mut state, key, value.clone());
+                Ok(value)
+            }
+        }
+    }
+
+    // Store a freshly fetched value, dropping entries too old to be served
+    fn insert(&self, state: &mut State<K, V>, key: K, value: V) {
+        let max_age = self.ttl + self.stale;
+        state
+            .entries
+            .retain(|_, entry| entry.fetched_at.elapsed() < max_age);
+        state.entries.insert(
+            key,
+            Entry {
+                value,
+                fetched_at: Instant::now(),
+                refreshing: false,
+            },
+        );
+    }
+
+    // Get how often the cache has been used and how many entries it holds
+    pub fn stats(&self) -> CacheStats {
+        let state = self.state.lock().unwrap();
+        CacheStats {
+            entries: state.entries.len(),
+            ..state.stats
+        }
+    }
+
+    // Drop every cached entry, returning how many there were
+    pub fn clear(&self) -> usize {
+        let mut state = self.state.lock().unwrap();
+        let cleared = state.entries.len();
+        state.entries.clear();
+        cleared
+    }
+}
+
+// Caches in front of each StackUp API endpoint, keyed by user ID for the per-user endpoints
+pub struct ApiCache {
+    api: Arc<dyn StackUpApi>,
+    campaigns: Arc<TtlCache<(), Vec<Campaigns>>>,
+    pathways: Arc<TtlCache<(), Vec<Pathways>>>,
+    hackathons: Arc<TtlCache<(), Vec<Hackathons>>>,
+    balance: Arc<TtlCache<i32, UserBalance>>,
+    progress: Arc<TtlCache<i32, UserProgress>>,
+    user_details: Arc<TtlCache<i32, GetUserResponse>>,
+}
+
+impl ApiCache {
+    // Put empty caches with the TTLs from the config in front of an API
+    pub fn new(api: Arc<dyn StackUpApi>, config: &CacheConfig) -> Self {
+        let stale = config.stale_while_revalidate;
+        Self {
+            api,
+            campaigns: Arc::new(TtlCache::new(config.campaigns_ttl, stale)),
+            pathways: Arc::new(TtlCache::new(config.pathways_ttl, stale)),
+            hackathons: Arc::new(TtlCache::new(config.hackathons_ttl, stale)),
+            balance: Arc::new(TtlCache::new(config.balance_ttl, stale)),
+            progress: Arc::new(TtlCache::new(config.progress_ttl, stale)),
+            user_details: Arc::new(TtlCache::new(config.user_details_ttl, stale)),
+        }
+    }
+
+    // Get the stats of every endpoint's cache, by endpoint name
+    pub fn stats(&self) -> Vec<(&'static str, CacheStats)> {
+        vec![
+            ("campaigns", self.campaigns.stats()),
+            ("pathways", self.pathways.stats()),
+            ("hackathons", self.hackathons.stats()),
+            ("balance", self.balance.stats()),
+            ("progress", self.progress.stats()),
+            ("user details", self.user_details.stats()),
+        ]
+    }
+
+    // Drop every cached response, returning how many there were
+    pub fn clear(&self) -> usize {
+        self.campaigns.clear()
+            + self.pathways.clear()
+            + self.hackathons.clear()
+            + self.balance.clear()
+            + self.progress.clear()
+            + self.user_details.clear()
+    }
+}
+
+// Each call is answered from the matching cache, which only calls the wrapped API when needed
+#[async_trait]
+impl StackUpApi for ApiCache {
+    async fn get_user_balance(&self, user_id: i32) -> Result<UserBalance, StackUpApiError> {
+        let api = Arc::clone(&self.api);
+        self.balance
+            .get_or_fetch(user_id, move || async move {
+                api.get_user_balance(user_id).await
+            })
+            .await
+    }
+
+    async fn get_user_progress(&self, user_id: i32) -> Result<UserProgress, StackUpApiError> {
+        let api = Arc::clone(&self.api);
+        self.progress
+            .get_or_fetch(user_id, move || async move {
+                api.get_user_progress(user_id).await
+            })
+            .await
+    }
+
+    async fn get_user_details(&self, user_id: i32) -> Result<GetUserResponse, StackUpApiError> {
+        let api = Arc::clone(&self.api);
+        self.user_details
+            .get_or_fetch(user_id, move || async move {
+                api.get_user_details(user_id).await
+            })
+            .await
+    }
+
+    async fn get_featured_campaigns(&self) -> Result<Vec<Campaigns>, StackUpApiError> {
+        let api = Arc::clone(&self.api);
+        self.campaigns
+            .get_or_fetch(
+                (),
+                move || async move { api.get_featured_campaigns().await },
+            )
+            .await
+    }
+
+    async fn get_pathways(&self) -> Result<Vec<Pathways>, StackUpApiError> {
+        let api = Arc::clone(&self.api);
+        self.pathways
+            .get_or_fetch((), move || async move { api.get_pathways().await })
+            .await
+    }
+
+    async fn get_hackathons(&self) -> Result<Vec<Hackathons>, StackUpApiError> {
+        let api = Arc::clone(&self.api);
+        self.hackathons
+            .get_or_fetch((), move || async move { api.get_hackathons().await })
+            .await
+    }
+}
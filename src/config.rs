@@ -0,0 +1,765 @@
+use reqwest::header::HeaderValue;
+use serenity::model::colour::Colour;
+use serenity::model::id::{ChannelId, GuildId, UserId};
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+// The config file read when `CONFIG_FILE` isn't set; it is optional, so the bot can run from
+// environment variables alone
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+// The bot's settings, read from a TOML file with environment variables taking precedence.
+// Each key can be overridden by the environment variable named after its path, e.g.
+// `api.base_url` by `API_BASE_URL` and `embed.colors.help` by `EMBED_COLORS_HELP`.
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub discord_token: Secret,
+    // The text commands start with, e.g. `!` for `!help`
+    pub command_prefix: String,
+    // Whether the prefix commands are handled alongside slash commands
+    pub prefix_commands: bool,
+    // Guild to register slash commands with; they are registered globally when unset
+    pub guild_id: Option<GuildId>,
+    // Guilds the bot answers in; every guild is allowed when empty
+    pub allowed_guilds: Vec<GuildId>,
+    // Members who can run the commands that act on the whole bot, like clearing the cache
+    pub owners: Vec<UserId>,
+    // The SQLite database the bot keeps its state in
+    pub database_file: PathBuf,
+    // How long command uses are kept for the usage statistics
+    pub usage_retention: Duration,
+    // How long a request can take before the animated loading embed is shown
+    pub loading_threshold: Duration,
+    pub log_level: LogLevel,
+    // Whether logs are written as plain text or as one JSON object per line
+    pub log_format: LogFormat,
+    pub api: ApiConfig,
+    pub embed: EmbedConfig,
+    pub cache: CacheConfig,
+    pub announcements: AnnouncementConfig,
+    pub cooldowns: CooldownConfig,
+}
+
+// A value that must never end up in logs; its `Debug` output leaves the value out
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Secret(value.into())
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(<redacted>)")
+    }
+}
+
+// Where the StackUp API lives and how long to wait for it
+#[derive(Clone, Debug)]
+pub struct ApiConfig {
+    pub base_url: String,
+    // Sent as a bearer token with every request when StackUp needs credentials
+    pub auth_token: Option<HeaderValue>,
+    pub connect_timeout: Duration,
+    pub request_timeout: Duration,
+    // How many times a failed request is retried before giving up
+    pub max_retries: u32,
+    // The delay before the first retry, doubled for each one after it up to `retry_max_delay`
+    pub retry_base_delay: Duration,
+    pub retry_max_delay: Duration,
+    // How many requests in a row have to fail before calls are short-circuited
+    pub breaker_threshold: u32,
+    // How long calls are short-circuited for before StackUp is tried again
+    pub breaker_cooldown: Duration,
+}
+
+// How long each endpoint's responses are cached; a TTL of zero turns caching off for it
+#[derive(Clone, Debug)]
+pub struct CacheConfig {
+    // How long past its TTL a response is still served while it is refreshed in the background
+    pub stale_while_revalidate: Duration,
+    pub campaigns_ttl: Duration,
+    pub pathways_ttl: Duration,
+    pub hackathons_ttl: Duration,
+    pub balance_ttl: Duration,
+    pub progress_ttl: Duration,
+    pub user_details_ttl: Duration,
+}
+
+// Where and how often new campaigns, hackathons and pathways are announced
+#[derive(Clone, Debug)]
+pub struct AnnouncementConfig {
+    // Channels new campaigns and hackathons are posted in; subscribers get DMs either way
+    pub channels: Vec<ChannelId>,
+    // How often StackUp is checked for anything new
+    pub poll_interval: Duration,
+}
+
+// How often commands can be used before members are asked to slow down
+#[derive(Clone, Debug)]
+pub struct CooldownConfig {
+    // How often each member can use a command
+    pub user: Rate,
+    // How often a command can be used in a channel, by everyone in it together
+    pub channel: Rate,
+    // Per-member rates for commands that need a different one, by command name
+    pub commands: HashMap<String, Rate>,
+}
+
+// A number of uses allowed in a period, e.g. `5/20` for five uses every 20 seconds.
+// The uses can be made in a burst, and come back gradually over the period.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rate {
+    pub uses: u32,
+    pub per: Duration,
+}
+
+// How the bot's embeds look
+#[derive(Clone, Debug)]
+pub struct EmbedConfig {
+    pub thumbnail_url: String,
+    // How many items long lists show per page
+    pub page_size: usize,
+    // How long a list's page buttons keep working after the last time one was pressed
+    pub page_timeout: Duration,
+    pub colors: EmbedColors,
+}
+
+// The accent color of each kind of embed
+#[derive(Clone, Debug)]
+pub struct EmbedColors {
+    pub help: Colour,
+    pub info: Colour,
+    pub success: Colour,
+    pub warning: Colour,
+    pub error: Colour,
+    pub username: Colour,
+    pub balance: Colour,
+    pub profile: Colour,
+    pub campaigns: Colour,
+    pub pathways: Colour,
+    pub hackathons: Colour,
+    pub calendar: Colour,
+}
+
+// How much the bot logs, from least to most verbose
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+// How log lines are written
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    // Human-readable lines, for reading in a terminal
+    Text,
+    // One JSON object per line, for log collectors
+    Json,
+}
+
+impl EmbedColors {
+    // Use a server's accent color for every kind of embed, keeping the success, warning and
+    // error colors so the outcome of a command still stands out
+    pub fn with_accent(&self, accent: Colour) -> EmbedColors {
+        EmbedColors {
+            help: accent,
+            info: accent,
+            username: accent,
+            balance: accent,
+            profile: accent,
+            campaigns: accent,
+            pathways: accent,
+            hackathons: accent,
+            calendar: accent,
+            ..self.clone()
+        }
+    }
+}
+
+impl FromStr for Rate {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (uses, secs) = value.split_once('/').ok_or(())?;
+        let uses = uses.trim().parse().map_err(|_| ())?;
+        let secs = secs.trim().parse().map_err(|_| ())?;
+        if uses == 0 || secs == 0 {
+            return Err(());
+        }
+        Ok(Rate {
+            uses,
+            per: Duration::from_secs(secs),
+        })
+    }
+}
+
+impl LogLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "error" => Ok(LogLevel::Error),
+            "warn" => Ok(LogLevel::Warn),
+            "info" => Ok(LogLevel::Info),
+            "debug" => Ok(LogLevel::Debug),
+            "trace" => Ok(LogLevel::Trace),
+            _ => Err(()),
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+// Everything wrong with the configuration, so it can all be fixed in one go
+#[derive(Debug)]
+pub enum ConfigError {
+    // The config file couldn't be read
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    // The config file isn't valid TOML
+    Syntax {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    // Keys that are missing or malformed
+    Invalid {
+        problems: Vec<String>,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => {
+                write!(f, "couldn't read {}: {}", path.display(), source)
+            }
+            ConfigError::Syntax { path, source } => {
+                write!(f, "{} isn't valid TOML: {}", path.display(), source)
+            }
+            ConfigError::Invalid { problems } => {
+                writeln!(f, "the configuration has {} problem(s):", problems.len())?;
+                for problem in problems {
+                    writeln!(f, "  - {}", problem)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io { source, .. } => Some(source),
+            ConfigError::Syntax { source, .. } => Some(source),
+            ConfigError::Invalid { .. } => None,
+        }
+    }
+}
+
+impl Config {
+    // Load the config file named by `CONFIG_FILE` (or `config.toml`), apply the environment
+    // overrides and validate the result
+    pub fn load() -> Result<Self, ConfigError> {
+        let (path, required) = match env::var("CONFIG_FILE") {
+            Ok(path) => (PathBuf::from(path), true),
+            Err(_) => (PathBuf::from(DEFAULT_CONFIG_FILE), false),
+        };
+
+        let table = match std::fs::read_to_string(&path) {
+            Ok(contents) => {
+                contents
+                    .parse::<toml::Table>()
+                    .map_err(|source| ConfigError::Syntax {
+                        path: path.clone(),
+                        source,
+                    })?
+            }
+            // Only an explicitly chosen config file has to exist
+            Err(err) if err.kind() == io::ErrorKind::NotFound && !required => toml::Table::new(),
+            Err(source) => return Err(ConfigError::Io { path, source }),
+        };
+
+        Self::from_table(table)
+    }
+
+    // Build the config from a parsed TOML table and the environment
+    pub fn from_table(table: toml::Table) -> Result<Self, ConfigError> {
+        let mut keys = Keys {
+            table,
+            problems: Vec::new(),
+        };
+
+        let config = Config {
+            discord_token: keys.required("discord_token", "a bot token", |value| {
+                non_empty(value).map(Secret::new)
+            }),
+            command_prefix: keys.value(
+                "command_prefix",
+                "!".to_string(),
+                "a prefix without spaces",
+                |value| {
+                    Some(value.to_string()).filter(|prefix| {
+                        !prefix.is_empty() && !prefix.contains(char::is_whitespace)
+                    })
+                },
+            ),
+            prefix_commands: keys.value("prefix_commands", true, "`true` or `false`", |value| {
+                value.parse().ok()
+            }),
+            guild_id: keys.optional("discord_guild_id", "a guild ID", parse_guild_id),
+            allowed_guilds: keys.value(
+                "allowed_guilds",
+                Vec::new(),
+                "a list of guild IDs",
+                |value| {
+                    value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|id| !id.is_empty())
+                        .map(parse_guild_id)
+                        .collect()
+                },
+            ),
+            owners: keys.value("owner_ids", Vec::new(), "a list of user IDs", |value| {
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .map(parse_user_id)
+                    .collect()
+            }),
+            database_file: keys.value(
+                "database_file",
+                PathBuf::from("stack-buddy.db"),
+                "a file path",
+                |value| non_empty(value).map(PathBuf::from),
+            ),
+            usage_retention: keys.value(
+                "usage_retention_days",
+                Duration::from_secs(90 * 24 * 60 * 60),
+                "a positive number of days",
+                |value| {
+                    value
+                        .parse::<u64>()
+                        .ok()
+                        .filter(|days| *days > 0)
+                        .map(|days| Duration::from_secs(days * 24 * 60 * 60))
+                },
+            ),
+            loading_threshold: keys.value(
+                "loading_threshold_ms",
+                Duration::from_millis(1000),
+                "a number of milliseconds",
+                |value| value.parse().ok().map(Duration::from_millis),
+            ),
+            log_level: keys.value(
+                "log_level",
+                LogLevel::Info,
+                "one of `error`, `warn`, `info`, `debug` or `trace`",
+                |value| value.parse().ok(),
+            ),
+            log_format: keys.value("log_format", LogFormat::Text, "`text` or `json`", |value| {
+                value.parse().ok()
+            }),
+            api: ApiConfig {
+                base_url: keys.value(
+                    "api.base_url",
+                    "https://superna.ytechno.com.ng/api".to_string(),
+                    "an http(s) URL",
+                    |value| parse_url(value).map(|url| url.trim_end_matches('/').to_string()),
+                ),
+                auth_token: keys.optional("api.auth_token", "a token without spaces", |value| {
+                    let mut header = HeaderValue::from_str(&format!("Bearer {}", value)).ok()?;
+                    header.set_sensitive(true);
+                    Some(header)
+                        .filter(|_| !value.is_empty() && !value.contains(char::is_whitespace))
+                }),
+                connect_timeout: keys.value(
+                    "api.connect_timeout_secs",
+                    Duration::from_secs(5),
+                    "a positive number of seconds",
+                    parse_seconds,
+                ),
+                request_timeout: keys.value(
+                    "api.request_timeout_secs",
+                    Duration::from_secs(15),
+                    "a positive number of seconds",
+                    parse_seconds,
+                ),
+                max_retries: keys
+                    .value("api.max_retries", 3, "a number", |value| value.parse().ok()),
+                retry_base_delay: keys.value(
+                    "api.retry_base_delay_ms",
+                    Duration::from_millis(250),
+                    "a number of milliseconds",
+                    |value| value.parse().ok().map(Duration::from_millis),
+                ),
+                retry_max_delay: keys.value(
+                    "api.retry_max_delay_ms",
+                    Duration::from_millis(5000),
+                    "a number of milliseconds",
+                    |value| value.parse().ok().map(Duration::from_millis),
+                ),
+                breaker_threshold: keys.value(
+                    "api.breaker_threshold",
+                    5,
+                    "a positive number",
+                    |value| value.parse().ok().filter(|threshold| *threshold > 0),
+                ),
+                breaker_cooldown: keys.value(
+                    "api.breaker_cooldown_secs",
+                    Duration::from_secs(30),
+                    "a positive number of seconds",
+                    parse_seconds,
+                ),
+            },
+            embed: EmbedConfig {
+                thumbnail_url: keys.value(
+                    "embed.thumbnail_url",
+                    "https://i.imgur.com/xRT3jsZ.png".to_string(),
+                    "an http(s) URL",
+                    parse_url,
+                ),
+                // Discord allows at most 25 fields per embed
+                page_size: keys.value("embed.page_size", 5, "a number from 1 to 25", |value| {
+                    value.parse().ok().filter(|size| (1..=25).contains(size))
+                }),
+                page_timeout: keys.value(
+                    "embed.page_timeout_secs",
+                    Duration::from_secs(120),
+                    "a positive number of seconds",
+                    parse_seconds,
+                ),
+                colors: EmbedColors {
+                    help: keys.color("embed.colors.help", Colour::DARK_GREEN),
+                    info: keys.color("embed.colors.info", Colour::RED),
+                    success: keys.color("embed.colors.success", Colour::DARK_GREEN),
+                    warning: keys.color("embed.colors.warning", Colour::ORANGE),
+                    error: keys.color("embed.colors.error", Colour::RED),
+                    username: keys.color("embed.colors.username", Colour::DARK_BLUE),
+                    balance: keys.color("embed.colors.balance", Colour::DARK_PURPLE),
+                    profile: keys.color("embed.colors.profile", Colour::DARK_GOLD),
+                    campaigns: keys.color("embed.colors.campaigns", Colour::DARK_ORANGE),
+                    pathways: keys.color("embed.colors.pathways", Colour::DARK_TEAL),
+                    hackathons: keys.color("embed.colors.hackathons", Colour::DARK_RED),
+                    calendar: keys.color("embed.colors.calendar", Colour::DARK_BLUE),
+                },
+            },
+            cache: CacheConfig {
+                stale_while_revalidate: keys.seconds("cache.stale_while_revalidate_secs", 300),
+                campaigns_ttl: keys.seconds("cache.campaigns_ttl_secs", 600),
+                pathways_ttl: keys.seconds("cache.pathways_ttl_secs", 600),
+                hackathons_ttl: keys.seconds("cache.hackathons_ttl_secs", 600),
+                balance_ttl: keys.seconds("cache.balance_ttl_secs", 30),
+                progress_ttl: keys.seconds("cache.progress_ttl_secs", 30),
+                user_details_ttl: keys.seconds("cache.user_details_ttl_secs", 300),
+            },
+            announcements: AnnouncementConfig {
+                channels: keys.value(
+                    "announcements.channels",
+                    Vec::new(),
+                    "a list of channel IDs",
+                    |value| {
+                        value
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|id| !id.is_empty())
+                            .map(parse_channel_id)
+                            .collect()
+                    },
+                ),
+                poll_interval: keys.value(
+                    "announcements.poll_interval_secs",
+                    Duration::from_secs(600),
+                    "a positive number of seconds",
+                    parse_seconds,
+                ),
+            },
+            cooldowns: CooldownConfig {
+                user: keys.rate("cooldowns.user", 5, 20),
+                channel: keys.rate("cooldowns.channel", 15, 30),
+                // The featured lists are the most expensive to answer
+                commands: keys.value(
+                    "cooldowns.commands",
+                    ["campaigns", "pathways", "hackathons"]
+                        .into_iter()
+                        .map(|name| {
+                            let rate = Rate {
+                                uses: 2,
+                                per: Duration::from_secs(30),
+                            };
+                            (name.to_string(), rate)
+                        })
+                        .collect(),
+                    "a list of command rates, like `[\"hackathons=2/30\"]`",
+                    |value| {
+                        value
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|rate| !rate.is_empty())
+                            .map(|rate| {
+                                let (name, rate) = rate.split_once('=')?;
+                                let name = name.trim().to_lowercase();
+                                Some((name, rate.parse().ok()?))
+                            })
+                            .collect()
+                    },
+                ),
+            },
+        };
+
+        if keys.problems.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError::Invalid {
+                problems: keys.problems,
+            })
+        }
+    }
+
+    // Check whether the bot should answer in a guild; direct messages are always answered
+    pub fn is_guild_allowed(&self, guild_id: Option<GuildId>) -> bool {
+        match guild_id {
+            Some(guild_id) => {
+                self.allowed_guilds.is_empty() || self.allowed_guilds.contains(&guild_id)
+            }
+            None => true,
+        }
+    }
+}
+
+// Reads keys from the environment or the config file, collecting every problem along the way
+struct Keys {
+    table: toml::Table,
+    problems: Vec<String>,
+}
+
+impl Keys {
+    // Get a key's raw value: its environment variable, or its value in the config file.
+    // Arrays are joined with commas, the same way list values are written in the environment.
+    fn raw(&mut self, key: &str) -> Option<String> {
+        let env_name = env_name(key);
+        if let Ok(value) = env::var(&env_name) {
+            return Some(value);
+        }
+
+        let mut path = key.split('.').peekable();
+        let mut table = &self.table;
+        let mut section = String::new();
+        while let Some(part) = path.next() {
+            let value = table.get(part)?;
+            if path.peek().is_some() {
+                section = if section.is_empty() {
+                    part.to_string()
+                } else {
+                    format!("{}.{}", section, part)
+                };
+                let Some(inner) = value.as_table() else {
+                    // Report a misplaced section once, not for every key inside it
+                    let problem = format!("`{}` should be a table, like `[{}]`", section, section);
+                    if !self.problems.contains(&problem) {
+                        self.problems.push(problem);
+                    }
+                    return None;
+                };
+                table = inner;
+                continue;
+            }
+
+            return match value {
+                toml::Value::String(value) => Some(value.clone()),
+                toml::Value::Array(values) => {
+                    let values: Vec<String> = values
+                        .iter()
+                        .map(|value| match value {
+                            toml::Value::String(value) => value.clone(),
+                            value => value.to_string(),
+                        })
+                        .collect();
+                    Some(values.join(","))
+                }
+                toml::Value::Table(_) => {
+                    self.problems
+                        .push(format!("`{}` should be a value, not a table", key));
+                    None
+                }
+                value => Some(value.to_string()),
+            };
+        }
+
+        None
+    }
+
+    // Get a key that may be left unset, recording a problem if it is set but malformed
+    fn optional<T>(
+        &mut self,
+        key: &str,
+        expected: &str,
+        parse: impl Fn(&str) -> Option<T>,
+    ) -> Option<T> {
+        let value = self.raw(key)?;
+        let parsed = parse(&value);
+        if parsed.is_none() {
+            // Never echo secrets back, even malformed ones
+            let found = if key.ends_with("token") {
+                "something else".to_string()
+            } else {
+                format!("`{}`", value)
+            };
+            self.problems.push(format!(
+                "`{}` ({}) should be {}, found {}",
+                key,
+                env_name(key),
+                expected,
+                found
+            ));
+        }
+
+        parsed
+    }
+
+    // Get a key, falling back to its default when it is unset or malformed
+    fn value<T>(
+        &mut self,
+        key: &str,
+        default: T,
+        expected: &str,
+        parse: impl Fn(&str) -> Option<T>,
+    ) -> T {
+        self.optional(key, expected, parse).unwrap_or(default)
+    }
+
+    // Get a key that must be set, recording a problem if it is missing
+    fn required<T: Default>(
+        &mut self,
+        key: &str,
+        expected: &str,
+        parse: impl Fn(&str) -> Option<T>,
+    ) -> T {
+        if self.raw(key).is_none() {
+            self.problems.push(format!(
+                "`{}` ({}) is missing, it should be {}",
+                key,
+                env_name(key),
+                expected
+            ));
+            return T::default();
+        }
+
+        self.value(key, T::default(), expected, parse)
+    }
+
+    // Get a number of seconds that may be zero
+    fn seconds(&mut self, key: &str, default: u64) -> Duration {
+        self.value(
+            key,
+            Duration::from_secs(default),
+            "a number of seconds",
+            |value| value.parse().ok().map(Duration::from_secs),
+        )
+    }
+
+    // Get a rate like `5/20`
+    fn rate(&mut self, key: &str, uses: u32, secs: u64) -> Rate {
+        let default = Rate {
+            uses,
+            per: Duration::from_secs(secs),
+        };
+        self.value(
+            key,
+            default,
+            "a number of uses per number of seconds, like `5/20`",
+            |value| value.parse().ok(),
+        )
+    }
+
+    // Get an embed color
+    fn color(&mut self, key: &str, default: Colour) -> Colour {
+        self.value(key, default, "a color like `#1F8B4C`", parse_color)
+    }
+}
+
+// The environment variable that overrides a key, e.g. `API_BASE_URL` for `api.base_url`
+fn env_name(key: &str) -> String {
+    key.replace('.', "_").to_uppercase()
+}
+
+// Parse a color written as `#RRGGBB`, `0xRRGGBB` or a plain number
+pub fn parse_color(value: &str) -> Option<Colour> {
+    let color = match value.strip_prefix('#').or_else(|| value.strip_prefix("0x")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    };
+    color.filter(|color| *color <= 0xFFFFFF).map(Colour::new)
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    Some(value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+fn parse_guild_id(value: &str) -> Option<GuildId> {
+    value.parse().ok().filter(|id| *id != 0).map(GuildId::new)
+}
+
+fn parse_user_id(value: &str) -> Option<UserId> {
+    value.parse().ok().filter(|id| *id != 0).map(UserId::new)
+}
+
+fn parse_channel_id(value: &str) -> Option<ChannelId> {
+    value.parse().ok().filter(|id| *id != 0).map(ChannelId::new)
+}
+
+fn parse_seconds(value: &str) -> Option<Duration> {
+    value
+        .parse()
+        .ok()
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+}
+
+fn parse_url(value: &str) -> Option<String> {
+    reqwest::Url::parse(value)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .map(|_| value.to_string())
+}
@@ -0,0 +1,141 @@
+use crate::config::{CooldownConfig, Rate};
+use serenity::model::id::{ChannelId, UserId};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// Once there are this many buckets, the ones that have filled back up are dropped
+const PRUNE_AT: usize = 1024;
+
+// What to do with a command after checking its cooldowns
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cooldown {
+    // Run it
+    Ready,
+    // Tell the member to slow down; they can use the command again after the given time
+    Notify(Duration),
+    // Drop it without a word, the member was already told to slow down
+    Silent,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum BucketKey {
+    User(UserId),
+    Channel(ChannelId),
+}
+
+// A token bucket: each use takes a token, and tokens come back one at a time at the rate's pace
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    // Whether the member was told to slow down since they last got to use the command
+    notified: bool,
+}
+
+impl Bucket {
+    fn full(rate: Rate, now: Instant) -> Self {
+        Bucket {
+            tokens: f64::from(rate.uses),
+            updated: now,
+            notified: false,
+        }
+    }
+
+    // Add the tokens that came back since the bucket was last updated
+    fn refill(&mut self, rate: Rate, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        let per_token = rate.per.as_secs_f64() / f64::from(rate.uses);
+        self.tokens = (self.tokens + elapsed / per_token).min(f64::from(rate.uses));
+        self.updated = now;
+    }
+
+    // How long until the next token comes back
+    fn wait(&self, rate: Rate) -> Duration {
+        let per_token = rate.per.as_secs_f64() / f64::from(rate.uses);
+        Duration::from_secs_f64((1.0 - self.tokens).max(0.0) * per_token)
+    }
+
+    fn is_full(&self, rate: Rate) -> bool {
+        self.tokens >= f64::from(rate.uses)
+    }
+}
+
+// Limits how often each command can be used, by each member and in each channel
+pub struct Cooldowns {
+    config: CooldownConfig,
+    buckets: Mutex<HashMap<(String, BucketKey), Bucket>>,
+}
+
+impl Cooldowns {
+    pub fn new(config: CooldownConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Check whether a member can use a command in a channel right now, using it up if so
+    pub fn check(&self, command: &str, user_id: UserId, channel_id: ChannelId) -> Cooldown {
+        self.check_at(command, user_id, channel_id, Instant::now())
+    }
+
+    // The same as `check`, as if it were called at `now`
+    pub fn check_at(
+        &self,
+        command: &str,
+        user_id: UserId,
+        channel_id: ChannelId,
+        now: Instant,
+    ) -> Cooldown {
+        let user_rate = self.rate(command, BucketKey::User(user_id));
+        let channel_rate = self.rate(command, BucketKey::Channel(channel_id));
+        let user_key = (command.to_string(), BucketKey::User(user_id));
+        let channel_key = (command.to_string(), BucketKey::Channel(channel_id));
+
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= PRUNE_AT {
+            buckets.retain(|(command, key), bucket| {
+                let rate = self.rate(command, *key);
+                bucket.refill(rate, now);
+                !bucket.is_full(rate)
+            });
+        }
+
+        // The command can only be used when both the member and the channel have a token left
+        let mut user = buckets
+            .remove(&user_key)
+            .unwrap_or_else(|| Bucket::full(user_rate, now));
+        let channel = buckets
+            .entry(channel_key)
+            .or_insert_with(|| Bucket::full(channel_rate, now));
+        user.refill(user_rate, now);
+        channel.refill(channel_rate, now);
+
+        let cooldown = if user.tokens >= 1.0 && channel.tokens >= 1.0 {
+            user.tokens -= 1.0;
+            channel.tokens -= 1.0;
+            user.notified = false;
+            Cooldown::Ready
+        } else if user.notified {
+            Cooldown::Silent
+        } else {
+            user.notified = true;
+            Cooldown::Notify(user.wait(user_rate).max(channel.wait(channel_rate)))
+        };
+        buckets.insert(user_key, user);
+        cooldown
+    }
+
+    // The rate a bucket fills back up at
+    fn rate(&self, command: &str, key: BucketKey) -> Rate {
+        match key {
+            BucketKey::User(_) => self
+                .config
+                .commands
+                .get(command)
+                .copied()
+                .unwrap_or(self.config.user),
+            BucketKey::Channel(_) => self.config.channel,
+        }
+    }
+}
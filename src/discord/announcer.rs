@@ -0,0 +1,177 @@
+use crate::api::StackUpApi;
+use crate::config::Config;
+use crate::discord::render;
+use crate::discord::response::Response;
+use crate::guild_settings::{parse_channel, Setting};
+use crate::store::Store;
+use crate::subscriptions::Topic;
+use serenity::builder::CreateMessage;
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::time::MissedTickBehavior;
+use tracing::{error, instrument, warn};
+
+// Something new on StackUp, ready to be posted
+#[derive(Clone, Debug, PartialEq)]
+pub struct Announcement {
+    pub topic: Topic,
+    pub response: Response,
+}
+
+// Checks StackUp for new campaigns, hackathons and pathways, and announces them in the
+// configured channels and to the members subscribed to them
+pub struct Announcer {
+    api: Arc<dyn StackUpApi>,
+    // Remembers what was seen in each feed and who is subscribed to it
+    store: Arc<dyn Store>,
+    config: Config,
+}
+
+impl Announcer {
+    pub fn new(api: Arc<dyn StackUpApi>, store: Arc<dyn Store>, config: Config) -> Self {
+        Self { api, store, config }
+    }
+
+    // Poll StackUp every `announcements.poll_interval` and post whatever is new, forever
+    pub async fn run(self, http: Arc<Http>) {
+        let mut interval = tokio::time::interval(self.config.announcements.poll_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            for announcement in self.poll().await {
+                self.announce(&http, &announcement).await;
+            }
+        }
+    }
+
+    // Find what has been added since the last poll. The very first poll of each topic only
+    // records what is already there, and what was seen is kept in the store, so restarting the
+    // bot doesn't announce everything again.
+    #[instrument(name = "announcer_poll", skip_all)]
+    pub async fn poll(&self) -> Vec<Announcement> {
+        let colors = &self.config.embed.colors;
+        let mut announcements = Vec::new();
+
+        match self.api.get_featured_campaigns().await {
+            Ok(campaigns) => {
+                let new = self.new_items(Topic::Campaigns, &campaigns, |c| c.id).await;
+                announcements.extend(new.into_iter().map(|campaign| Announcement {
+                    topic: Topic::Campaigns,
+                    response: render::new_campaign(campaign, colors),
+                }));
+            }
+            Err(why) => warn!(error = %why, "Error polling featured campaigns"),
+        }
+
+        match self.api.get_hackathons().await {
+            Ok(hackathons) => {
+                let new = self
+                    .new_items(Topic::Hackathons, &hackathons, |h| h.id)
+                    .await;
+                announcements.extend(new.into_iter().map(|hackathon| Announcement {
+                    topic: Topic::Hackathons,
+                    response: render::new_hackathon(hackathon, colors),
+                }));
+            }
+            Err(why) => warn!(error = %why, "Error polling hackathons"),
+        }
+
+        match self.api.get_pathways().await {
+            Ok(pathways) => {
+                let new = self.new_items(Topic::Pathways, &pathways, |p| p.id).await;
+                announcements.extend(new.into_iter().map(|pathway| Announcement {
+                    topic: Topic::Pathways,
+                    response: render::new_pathway(pathway, colors),
+                }));
+            }
+            Err(why) => warn!(error = %why, "Error polling featured pathways"),
+        }
+
+        announcements
+    }
+
+    // Post an announcement in the announcement channels and DM it to the topic's subscribers
+    async fn announce(&self, http: &Http, announcement: &Announcement) {
+        let embed = announcement
+            .response
+            .to_embed(&self.config.embed.thumbnail_url);
+
+        // Pathways are only sent to their subscribers, the channels get campaigns and hackathons
+        if announcement.topic != Topic::Pathways {
+            for channel_id in self.channels().await {
+                let message = CreateMessage::new().embed(embed.clone());
+                if let Err(why) = channel_id.send_message(http, message).await {
+                    error!(channel = channel_id.get(), error = ?why, "Error posting announcement");
+                }
+            }
+        }
+
+        let subscribers = match self.store.subscribers(announcement.topic).await {
+            Ok(subscribers) => subscribers,
+            Err(why) => {
+                error!(topic = %announcement.topic, error = ?why, "Error getting subscribers");
+                return;
+            }
+        };
+        for subscriber in subscribers {
+            let message = CreateMessage::new().embed(embed.clone());
+            if let Err(why) = subscriber.direct_message(http, message).await {
+                error!(user = subscriber.get(), error = ?why, "Error sending announcement");
+            }
+        }
+    }
+
+    // The configured announcement channels, plus the one each allowed guild has chosen
+    pub async fn channels(&self) -> Vec<ChannelId> {
+        let mut channels = self.config.announcements.channels.clone();
+        match self
+            .store
+            .guilds_with_setting(Setting::AnnouncementChannel.key())
+            .await
+        {
+            Ok(guilds) => channels.extend(
+                guilds
+                    .iter()
+                    .filter(|(guild_id, _)| self.config.is_guild_allowed(Some(*guild_id)))
+                    .filter_map(|(_, channel)| parse_channel(channel)),
+            ),
+            Err(why) => error!(error = ?why, "Error getting announcement channels"),
+        }
+        channels.sort();
+        channels.dedup();
+        channels
+    }
+
+    // Find the items whose IDs weren't there last time, and remember the current IDs for next
+    // time. Nothing is announced when the store can't be read or written, since it would be
+    // announced again on the next poll.
+    async fn new_items<'a, T>(
+        &self,
+        topic: Topic,
+        items: &'a [T],
+        id: impl Fn(&T) -> i32,
+    ) -> Vec<&'a T> {
+        let previous = match self.store.seen_items(topic).await {
+            Ok(previous) => previous,
+            Err(why) => {
+                error!(%topic, error = ?why, "Error getting seen items");
+                return Vec::new();
+            }
+        };
+        let ids: HashSet<i32> = items.iter().map(&id).collect();
+        if let Err(why) = self.store.save_seen_items(topic, &ids).await {
+            error!(%topic, error = ?why, "Error saving seen items");
+            return Vec::new();
+        }
+
+        match previous {
+            Some(previous) => items
+                .iter()
+                .filter(|item| !previous.contains(&id(item)))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
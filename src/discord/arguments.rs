@@ -0,0 +1,253 @@
+use serenity::model::application::{CommandInteraction, ResolvedValue};
+use serenity::model::id::UserId;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+// Errors from reading a command's arguments, shown to the user along with the command's usage
+#[derive(Debug)]
+pub enum ArgError {
+    // A quoted argument was never closed
+    UnclosedQuote,
+    // A `--switch=value` was given to a switch, which is either on or off
+    SwitchValue {
+        name: String,
+    },
+    // A required argument wasn't given
+    Missing {
+        name: String,
+    },
+    // An argument couldn't be converted to the expected type
+    Invalid {
+        name: String,
+        value: String,
+        expected: &'static str,
+    },
+}
+
+impl ArgError {
+    pub fn missing(name: &str) -> Self {
+        ArgError::Missing {
+            name: name.to_string(),
+        }
+    }
+
+    pub fn invalid(name: &str, value: impl ToString, expected: &'static str) -> Self {
+        ArgError::Invalid {
+            name: name.to_string(),
+            value: value.to_string(),
+            expected,
+        }
+    }
+}
+
+impl fmt::Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgError::UnclosedQuote => write!(f, "A quoted argument is missing its closing `\"`."),
+            ArgError::SwitchValue { name } => {
+                write!(f, "`--{}` is a switch and doesn't take a value.", name)
+            }
+            ArgError::Missing { name } => write!(f, "The `{}` argument is required.", name),
+            ArgError::Invalid {
+                name,
+                value,
+                expected,
+            } => write!(
+                f,
+                "`{}` isn't a valid `{}`, expected {}.",
+                value, name, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ArgError {}
+
+// The arguments of a prefix command: positional arguments and `--flag [value]` options
+#[derive(Debug, Default)]
+pub struct ParsedArgs {
+    pub positional: Vec<String>,
+    pub flags: HashMap<String, Option<String>>,
+}
+
+// Split the text after a prefix command's name into arguments.
+// Arguments are separated by whitespace unless wrapped in double quotes. `--flag value` and
+// `--flag=value` set an option; a flag followed by another flag or nothing at all is a switch.
+// The flags named in `switches` never take a value, so whatever follows them stays a
+// positional argument.
+pub fn parse_args(input: &str, switches: &[&str]) -> Result<ParsedArgs, ArgError> {
+    let mut parsed = ParsedArgs::default();
+    let mut tokens = tokenize(input)?.into_iter().peekable();
+
+    while let Some((token, quoted)) = tokens.next() {
+        let flag = if quoted {
+            None
+        } else {
+            token.strip_prefix("--")
+        };
+        match flag {
+            Some(flag) => {
+                let is_switch = |name: &str| switches.iter().any(|s| s.eq_ignore_ascii_case(name));
+                let (name, value) = match flag.split_once('=') {
+                    Some((name, _)) if is_switch(name) => {
+                        return Err(ArgError::SwitchValue {
+                            name: name.to_lowercase(),
+                        })
+                    }
+                    Some((name, value)) => (name, Some(value.to_string())),
+                    None if is_switch(flag) => (flag, None),
+                    None => {
+                        // Take the next token as the value unless it is another flag
+                        let value = tokens
+                            .next_if(|(next, quoted)| *quoted || !next.starts_with("--"))
+                            .map(|(next, _)| next);
+                        (flag, value)
+                    }
+                };
+                parsed.flags.insert(name.to_lowercase(), value);
+            }
+            None => parsed.positional.push(token),
+        }
+    }
+
+    Ok(parsed)
+}
+
+// Split the input on whitespace, keeping quoted strings together.
+// Each token is paired with whether it was quoted, so quoted text is never treated as a flag.
+fn tokenize(input: &str) -> Result<Vec<(String, bool)>, ArgError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        // Mobile keyboards often insert curly quotes, so accept those too
+        if matches!(c, '"' | '“' | '”') {
+            chars.next();
+            let mut token = String::new();
+            loop {
+                match chars.next() {
+                    Some('"' | '“' | '”') => break,
+                    Some(c) => token.push(c),
+                    None => return Err(ArgError::UnclosedQuote),
+                }
+            }
+            tokens.push((token, true));
+        } else {
+            let mut token = String::new();
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                token.push(c);
+            }
+            tokens.push((token, false));
+        }
+    }
+
+    Ok(tokens)
+}
+
+// Parse a user mention (`<@123>` or `<@!123>`) or a raw user ID
+pub fn parse_user_mention(value: &str) -> Option<UserId> {
+    let id = value
+        .strip_prefix("<@")
+        .and_then(|value| value.strip_suffix('>'))
+        .map(|value| value.trim_start_matches('!'))
+        .unwrap_or(value);
+
+    id.parse().ok().filter(|id| *id != 0).map(UserId::new)
+}
+
+// Arguments passed to a command, either parsed from a prefix message or taken from slash options
+pub enum CommandArgs<'a> {
+    Prefix(ParsedArgs),
+    Slash(&'a CommandInteraction),
+}
+
+impl CommandArgs<'_> {
+    // Get an argument by its position in a prefix command, or by its option name in a slash command
+    pub fn get(&self, index: usize, name: &str) -> Option<String> {
+        match self {
+            CommandArgs::Prefix(args) => args.positional.get(index).cloned(),
+            CommandArgs::Slash(command) => slash_option(command, name),
+        }
+    }
+
+    // Get every positional argument from `index` on, joined by spaces, for values that can
+    // contain spaces; slash commands take the whole value as a single option
+    pub fn rest(&self, index: usize, name: &str) -> Option<String> {
+        match self {
+            CommandArgs::Prefix(args) => {
+                Some(args.positional.get(index..)?.join(" ")).filter(|rest| !rest.is_empty())
+            }
+            CommandArgs::Slash(command) => slash_option(command, name),
+        }
+    }
+
+    // Get an argument converted to a type, describing what was `expected` if it doesn't convert
+    pub fn parse<T: FromStr>(
+        &self,
+        index: usize,
+        name: &str,
+        expected: &'static str,
+    ) -> Result<Option<T>, ArgError> {
+        match self.get(index, name) {
+            Some(value) => match value.parse() {
+                Ok(parsed) => Ok(Some(parsed)),
+                Err(_) => Err(ArgError::invalid(name, value, expected)),
+            },
+            None => Ok(None),
+        }
+    }
+
+    // Get an argument that mentions a Discord user
+    pub fn user(&self, index: usize, name: &str) -> Result<Option<UserId>, ArgError> {
+        match self.get(index, name) {
+            Some(value) => match parse_user_mention(&value) {
+                Some(user_id) => Ok(Some(user_id)),
+                None => Err(ArgError::invalid(
+                    name,
+                    value,
+                    "a user mention like @someone",
+                )),
+            },
+            None => Ok(None),
+        }
+    }
+
+    // Get the value of a `--flag value` option, or the slash option with the same name
+    pub fn flag(&self, name: &str) -> Option<String> {
+        match self {
+            CommandArgs::Prefix(args) => args.flags.get(name).cloned().flatten(),
+            CommandArgs::Slash(command) => slash_option(command, name),
+        }
+    }
+
+    // Check whether a `--flag` switch is set, or the boolean slash option with the same name is true
+    pub fn has_flag(&self, name: &str) -> bool {
+        match self {
+            CommandArgs::Prefix(args) => args.flags.contains_key(name),
+            CommandArgs::Slash(command) => slash_option(command, name).is_some_and(|v| v == "true"),
+        }
+    }
+}
+
+// Get the value of a slash command option as a string
+fn slash_option(command: &CommandInteraction, name: &str) -> Option<String> {
+    command
+        .data
+        .options()
+        .into_iter()
+        .find(|option| option.name == name)
+        .and_then(|option| match option.value {
+            ResolvedValue::String(value) => Some(value.to_string()),
+            ResolvedValue::Integer(value) => Some(value.to_string()),
+            ResolvedValue::Number(value) => Some(value.to_string()),
+            ResolvedValue::Boolean(value) => Some(value.to_string()),
+            ResolvedValue::User(user, _) => Some(user.id.to_string()),
+            _ => None,
+        })
+}
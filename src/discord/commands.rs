@@ -1,10 +1,248 @@
-use crate::api::{
-    get_featured_campaigns, get_hackathons, get_pathways, get_user_balance, get_user_details,
-    get_user_progress,
-};
-use crate::discord::discord::send_embed_message;
-use crate::discord::messages::{HELP_MESSAGE, INFO_MESSAGE};
-use crate::util::show_loading_animation;
-use serenity::model::colour::Colour;
-use serenity::model::prelude::*;
-use serenity::prelude::*;
+use crate::discord::arguments::{ArgError, CommandArgs};
+use crate::discord::discord::{Caller, Handler};
+use crate::discord::messages::{HELP_FOOTER, HELP_HEADER};
+use crate::discord::response::Response;
+use crate::guild_settings::GuildSettings;
+use serenity::async_trait;
+use serenity::builder::{CreateCommand, CreateCommandOption};
+use serenity::model::permissions::Permissions;
+
+pub mod account;
+pub mod admin;
+pub mod featured;
+pub mod general;
+
+// A bot command, available both as a `!` prefix command and as a slash command
+#[async_trait]
+pub trait Command: Send + Sync {
+    // The command's name, used for the slash command and the main prefix command
+    fn name(&self) -> &'static str;
+
+    // Other names the prefix command also answers to
+    fn aliases(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    // A one-line description shown in `!help` and the slash command picker
+    fn description(&self) -> &'static str;
+
+    // The arguments the command takes, e.g. `<stackup-id>`
+    fn usage(&self) -> &'static str {
+        ""
+    }
+
+    // A short description of each argument in `usage`, as (name, description) pairs
+    fn arguments(&self) -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+
+    // Example invocations shown in the command's detailed help, written without the prefix for
+    // prefix commands (e.g. `link 42`) or starting with `/` for slash commands
+    fn examples(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    // The `--flags` that are switches, which are either given or not and never take a value
+    fn switches(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    // The permissions a member needs to run the command
+    fn required_permissions(&self) -> Permissions {
+        Permissions::empty()
+    }
+
+    // Whether only the bot's owners can run the command, because it acts on the bot as a whole
+    // rather than on the server it is run in
+    fn owner_only(&self) -> bool {
+        false
+    }
+
+    // Whether the command looks at the caller's permissions beyond `required_permissions`, so
+    // the dispatcher knows to look them up
+    fn uses_permissions(&self) -> bool {
+        false
+    }
+
+    // The typed options of the slash command, matching the arguments in `usage`
+    fn options(&self) -> Vec<CreateCommandOption> {
+        Vec::new()
+    }
+
+    // Suggest values for the option currently being typed in the slash command
+    async fn autocomplete(
+        &self,
+        _handler: &Handler,
+        _caller: &Caller,
+        _partial: &str,
+    ) -> Vec<String> {
+        Vec::new()
+    }
+
+    // Run the command and return the response to send, or an error if its arguments are wrong
+    // so the usage can be shown instead
+    async fn run(
+        &self,
+        handler: &Handler,
+        caller: &Caller,
+        args: &CommandArgs<'_>,
+    ) -> Result<Response, ArgError>;
+}
+
+// Format how a command is typed with the given prefix, e.g. `!link <stackup-id>`
+pub fn command_signature(command: &dyn Command, prefix: &str) -> String {
+    match command.usage() {
+        "" => format!("{}{}", prefix, command.name()),
+        usage => format!("{}{} {}", prefix, command.name(), usage),
+    }
+}
+
+// Check whether a member with the given permissions may run a command
+pub fn is_permitted(command: &dyn Command, permissions: Permissions) -> bool {
+    permissions.contains(Permissions::ADMINISTRATOR)
+        || permissions.contains(command.required_permissions())
+}
+
+// Check whether the caller can run a command in a server with these settings, the way the help
+// and its suggestions decide which commands to show
+pub fn is_available(command: &dyn Command, caller: &Caller, settings: &GuildSettings) -> bool {
+    (caller.is_owner || !command.owner_only())
+        && is_permitted(command, caller.permissions)
+        && !settings.is_disabled(command.name())
+}
+
+// Build the detailed help for a single command: usage, arguments, examples and aliases
+pub fn command_help(command: &dyn Command, prefix: &str) -> String {
+    let mut help = format!(
+        "**`{}`**\n*{}*\n",
+        command_signature(command, prefix),
+        command.description()
+    );
+
+    if !command.arguments().is_empty() {
+        help.push_str("\n**Arguments:**\n");
+        for (name, description) in command.arguments() {
+            help.push_str(&format!("• `{}` - {}\n", name, description));
+        }
+    }
+
+    if !command.examples().is_empty() {
+        help.push_str("\n**Examples:**\n");
+        for example in command.examples() {
+            // Slash command examples are shown as they are, the rest get the prefix
+            let prefix = if example.starts_with('/') { "" } else { prefix };
+            help.push_str(&format!("• `{}{}`\n", prefix, example));
+        }
+    }
+
+    if !command.aliases().is_empty() {
+        let aliases: Vec<String> = command
+            .aliases()
+            .iter()
+            .map(|alias| format!("`{}{}`", prefix, alias))
+            .collect();
+        help.push_str(&format!("\n**Aliases:** {}\n", aliases.join(", ")));
+    }
+
+    help.push_str(&format!("\nAlso available as **`/{}`**.", command.name()));
+    help
+}
+
+// Every command the bot knows about; the dispatcher, `!help` and slash commands are built from it
+pub struct CommandRegistry {
+    commands: Vec<Box<dyn Command>>,
+}
+
+impl CommandRegistry {
+    // Create an empty registry
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+        }
+    }
+
+    // Add a command to the registry
+    pub fn register(&mut self, command: impl Command + 'static) -> &mut Self {
+        self.commands.push(Box::new(command));
+        self
+    }
+
+    // Iterate over the registered commands in the order they were registered
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Command> {
+        self.commands.iter().map(|command| command.as_ref())
+    }
+
+    // Find a command by its name or one of its aliases
+    pub fn find(&self, name: &str) -> Option<&dyn Command> {
+        self.iter()
+            .find(|command| command.name() == name || command.aliases().contains(&name))
+    }
+
+    // Build the `!help` command list, one line per command the caller can run in a server with
+    // these settings
+    pub fn help_message(&self, caller: &Caller, settings: &GuildSettings) -> String {
+        let commands: String = self
+            .iter()
+            .filter(|command| is_available(*command, caller, settings))
+            .map(|command| {
+                format!(
+                    "• **`{}`** - *{}*\n",
+                    command_signature(command, &caller.prefix),
+                    command.description()
+                )
+            })
+            .collect();
+
+        format!("{}{}{}", HELP_HEADER, commands, HELP_FOOTER)
+    }
+
+    // Build the slash command definitions for every registered command
+    pub fn slash_commands(&self) -> Vec<CreateCommand> {
+        self.iter()
+            .map(|command| {
+                let slash_command = CreateCommand::new(command.name())
+                    .description(command.description())
+                    .set_options(command.options());
+
+                // Let Discord hide restricted commands from members who can't run them. Discord
+                // can't hide commands from everyone but the bot's owners, so theirs are left to
+                // administrators' menus.
+                let permissions = if command.owner_only() {
+                    Permissions::ADMINISTRATOR
+                } else {
+                    command.required_permissions()
+                };
+                match permissions {
+                    permissions if permissions.is_empty() => slash_command,
+                    permissions => slash_command.default_member_permissions(permissions),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for CommandRegistry {
+    // Create a registry holding all of the built-in commands
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry
+            .register(general::Help)
+            .register(general::Info)
+            .register(account::Link)
+            .register(account::Unlink)
+            .register(account::Username)
+            .register(account::Balance)
+            .register(account::Profile)
+            .register(account::Privacy)
+            .register(featured::Campaigns)
+            .register(featured::Pathways)
+            .register(featured::Hackathons)
+            .register(featured::Subscribe)
+            .register(featured::Unsubscribe)
+            .register(general::Calendar)
+            .register(admin::Config)
+            .register(admin::CacheStats)
+            .register(admin::CacheClear);
+        registry
+    }
+}
@@ -0,0 +1,454 @@
+use crate::discord::arguments::{ArgError, CommandArgs};
+use crate::discord::commands::Command;
+use crate::discord::discord::{Caller, Handler};
+use crate::discord::messages::NOT_LINKED_MESSAGE;
+use crate::discord::render;
+use crate::discord::response::Response;
+use serenity::async_trait;
+use serenity::builder::CreateCommandOption;
+use serenity::model::application::CommandOptionType;
+use serenity::model::id::UserId;
+
+pub struct Link;
+
+#[async_trait]
+impl Command for Link {
+    fn name(&self) -> &'static str {
+        "link"
+    }
+
+    fn description(&self) -> &'static str {
+        "Link your Discord account to your StackUp user ID."
+    }
+
+    fn usage(&self) -> &'static str {
+        "<stackup-id>"
+    }
+
+    fn arguments(&self) -> &'static [(&'static str, &'static str)] {
+        &[("stackup-id", "Your StackUp user ID, a positive number.")]
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        &["link 42", "/link stackup-id:42"]
+    }
+
+    fn options(&self) -> Vec<CreateCommandOption> {
+        vec![CreateCommandOption::new(
+            CommandOptionType::Integer,
+            "stackup-id",
+            "Your StackUp user ID",
+        )
+        .required(true)
+        .min_int_value(1)]
+    }
+
+    async fn run(
+        &self,
+        handler: &Handler,
+        caller: &Caller,
+        args: &CommandArgs<'_>,
+    ) -> Result<Response, ArgError> {
+        // Read the StackUp user ID, which must be a positive number
+        let stackup_id: i32 = args
+            .parse(0, "stackup-id", "a positive number")?
+            .ok_or_else(|| ArgError::missing("stackup-id"))?;
+        if stackup_id <= 0 {
+            return Err(ArgError::invalid(
+                "stackup-id",
+                stackup_id,
+                "a positive number",
+            ));
+        }
+
+        // Save the link and let the user know how it went
+        let response = match handler.store.link(caller.id, stackup_id).await {
+            Ok(()) => {
+                let link_message_details = format!(
+                    "Your Discord account is now linked to StackUp user ID **{}**.",
+                    stackup_id
+                );
+                Response::new(link_message_details, caller.colors.success)
+            }
+            // Let the user know if saving the link fails
+            Err(err) => handler.error_response(
+                "Error saving account link",
+                "Your account link couldn't be saved. Please try again later.",
+                &err,
+                caller,
+            ),
+        };
+
+        Ok(response)
+    }
+}
+
+pub struct Unlink;
+
+#[async_trait]
+impl Command for Unlink {
+    fn name(&self) -> &'static str {
+        "unlink"
+    }
+
+    fn description(&self) -> &'static str {
+        "Remove the link to your StackUp user ID."
+    }
+
+    async fn run(
+        &self,
+        handler: &Handler,
+        caller: &Caller,
+        _args: &CommandArgs<'_>,
+    ) -> Result<Response, ArgError> {
+        // Remove the link and let the user know how it went
+        let response = match handler.store.unlink(caller.id).await {
+            Ok(Some(stackup_id)) => {
+                let unlink_message_details = format!(
+                    "Your Discord account is no longer linked to StackUp user ID **{}**.",
+                    stackup_id
+                );
+                Response::new(unlink_message_details, caller.colors.success)
+            }
+            Ok(None) => Response::new(NOT_LINKED_MESSAGE, caller.colors.warning),
+            // Let the user know if removing the link fails
+            Err(err) => handler.error_response(
+                "Error removing account link",
+                "Your account link couldn't be removed. Please try again later.",
+                &err,
+                caller,
+            ),
+        };
+
+        Ok(response)
+    }
+}
+
+pub struct Username;
+
+#[async_trait]
+impl Command for Username {
+    fn name(&self) -> &'static str {
+        "username"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["get-username"]
+    }
+
+    fn description(&self) -> &'static str {
+        "Retrieve your (or another member's) StackUp username."
+    }
+
+    fn usage(&self) -> &'static str {
+        "[@member]"
+    }
+
+    fn arguments(&self) -> &'static [(&'static str, &'static str)] {
+        &[(
+            "member",
+            "Optional. Look up another member instead of yourself.",
+        )]
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        &["username", "username @someone"]
+    }
+
+    fn options(&self) -> Vec<CreateCommandOption> {
+        vec![CreateCommandOption::new(
+            CommandOptionType::User,
+            "member",
+            "Look up another member instead of yourself",
+        )]
+    }
+
+    async fn run(
+        &self,
+        handler: &Handler,
+        caller: &Caller,
+        args: &CommandArgs<'_>,
+    ) -> Result<Response, ArgError> {
+        // Resolve the linked StackUp account of the mentioned member, or the caller
+        let target = args.user(0, "member")?;
+        let account = match handler.linked_account(caller, target).await {
+            Ok(account) => account,
+            Err(response) => return Ok(response),
+        };
+        let user_id = account.stackup_id;
+
+        // Fetch user details and handle the result
+        let response = match handler.api.get_user_details(user_id).await {
+            Ok(user) => render::user_details(&user, &caller.colors),
+            // Let the user know if fetching user details fails
+            Err(err) => handler.api_error_response("Error getting user details", &err, caller),
+        };
+
+        Ok(response)
+    }
+}
+
+pub struct Balance;
+
+#[async_trait]
+impl Command for Balance {
+    fn name(&self) -> &'static str {
+        "balance"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["get-balance"]
+    }
+
+    fn description(&self) -> &'static str {
+        "Check your (or another member's) StackUp balance breakdown."
+    }
+
+    fn usage(&self) -> &'static str {
+        "[@member] [--private]"
+    }
+
+    fn arguments(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            (
+                "member",
+                "Optional. Look up another member instead of yourself.",
+            ),
+            (
+                "--private",
+                "Optional. Send the balance to your DMs instead of the channel.",
+            ),
+        ]
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        &[
+            "balance",
+            "balance @someone",
+            "balance --private",
+            "/balance private:True",
+        ]
+    }
+
+    fn switches(&self) -> &'static [&'static str] {
+        &["private"]
+    }
+
+    fn options(&self) -> Vec<CreateCommandOption> {
+        vec![
+            CreateCommandOption::new(
+                CommandOptionType::User,
+                "member",
+                "Look up another member instead of yourself",
+            ),
+            CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "private",
+                "Send the balance to your DMs instead of the channel",
+            ),
+        ]
+    }
+
+    async fn run(
+        &self,
+        handler: &Handler,
+        caller: &Caller,
+        args: &CommandArgs<'_>,
+    ) -> Result<Response, ArgError> {
+        // Resolve the linked StackUp account of the mentioned member, or the caller
+        let target = args.user(0, "member")?;
+        let account = match handler.linked_account(caller, target).await {
+            Ok(account) => account,
+            Err(response) => return Ok(response),
+        };
+        let user_id = account.stackup_id;
+
+        // Other members only get to see the balance if its owner has made it public
+        let discord_id = target.unwrap_or(caller.id);
+        let is_caller = discord_id == caller.id;
+        if !is_caller && account.private {
+            let private_message_details =
+                format!("<@{}> keeps their StackUp balance private.", discord_id);
+            return Ok(Response::new(
+                private_message_details,
+                caller.colors.warning,
+            ));
+        }
+
+        // Fetch user balance and handle the result, sending it to the caller's DMs when asked
+        let response = match handler.api.get_user_balance(user_id).await {
+            Ok(balance) => render::balance(&owner(caller, discord_id), &balance, &caller.colors)
+                .direct(args.has_flag("private")),
+            // Let the user know if fetching user balance fails
+            Err(err) => handler.api_error_response("Error getting user balance", &err, caller),
+        };
+
+        Ok(response)
+    }
+}
+
+pub struct Profile;
+
+#[async_trait]
+impl Command for Profile {
+    fn name(&self) -> &'static str {
+        "profile"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["get-profile"]
+    }
+
+    fn description(&self) -> &'static str {
+        "Check your (or another member's) StackUp profile."
+    }
+
+    fn usage(&self) -> &'static str {
+        "[@member]"
+    }
+
+    fn arguments(&self) -> &'static [(&'static str, &'static str)] {
+        &[(
+            "member",
+            "Optional. Look up another member instead of yourself.",
+        )]
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        &["profile", "profile @someone"]
+    }
+
+    fn options(&self) -> Vec<CreateCommandOption> {
+        vec![CreateCommandOption::new(
+            CommandOptionType::User,
+            "member",
+            "Look up another member instead of yourself",
+        )]
+    }
+
+    async fn run(
+        &self,
+        handler: &Handler,
+        caller: &Caller,
+        args: &CommandArgs<'_>,
+    ) -> Result<Response, ArgError> {
+        // Resolve the linked StackUp account of the mentioned member, or the caller
+        let target = args.user(0, "member")?;
+        let account = match handler.linked_account(caller, target).await {
+            Ok(account) => account,
+            Err(response) => return Ok(response),
+        };
+        let user_id = account.stackup_id;
+        // Earnings count as part of the balance, so they are hidden from others when private
+        let discord_id = target.unwrap_or(caller.id);
+        let is_caller = discord_id == caller.id;
+        let show_earnings = is_caller || !account.private;
+
+        // Fetch user profile and handle the result
+        let response = match handler.api.get_user_progress(user_id).await {
+            Ok(profile) => render::progress(
+                &owner(caller, discord_id),
+                &profile,
+                show_earnings,
+                &caller.colors,
+            ),
+            // Let the user know if fetching user profile fails
+            Err(err) => handler.api_error_response("Error getting user profile", &err, caller),
+        };
+
+        Ok(response)
+    }
+}
+
+pub struct Privacy;
+
+#[async_trait]
+impl Command for Privacy {
+    fn name(&self) -> &'static str {
+        "privacy"
+    }
+
+    fn description(&self) -> &'static str {
+        "Choose whether other members can see your StackUp balance."
+    }
+
+    fn usage(&self) -> &'static str {
+        "<public|private>"
+    }
+
+    fn arguments(&self) -> &'static [(&'static str, &'static str)] {
+        &[(
+            "setting",
+            "`public` to let other members see your balance, `private` to hide it (the default).",
+        )]
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        &["privacy public", "privacy private"]
+    }
+
+    fn options(&self) -> Vec<CreateCommandOption> {
+        vec![CreateCommandOption::new(
+            CommandOptionType::String,
+            "setting",
+            "Who can see your StackUp balance",
+        )
+        .required(true)
+        .add_string_choice("Public - anyone can see it", "public")
+        .add_string_choice("Private - only you can see it", "private")]
+    }
+
+    async fn run(
+        &self,
+        handler: &Handler,
+        caller: &Caller,
+        args: &CommandArgs<'_>,
+    ) -> Result<Response, ArgError> {
+        // Read whether the balance should be private
+        let setting = args
+            .get(0, "setting")
+            .ok_or_else(|| ArgError::missing("setting"))?;
+        let private = match setting.to_lowercase().as_str() {
+            "public" => false,
+            "private" => true,
+            _ => {
+                return Err(ArgError::invalid(
+                    "setting",
+                    setting,
+                    "`public` or `private`",
+                ))
+            }
+        };
+
+        // Save the setting and let the user know how it went
+        let response = match handler.store.set_private(caller.id, private).await {
+            Ok(true) => {
+                let privacy_message_details = if private {
+                    "Your StackUp balance is now hidden from other members."
+                } else {
+                    "Other members can now see your StackUp balance."
+                };
+                Response::new(privacy_message_details, caller.colors.success)
+            }
+            Ok(false) => Response::new(NOT_LINKED_MESSAGE, caller.colors.warning),
+            // Let the user know if saving the setting fails
+            Err(err) => handler.error_response(
+                "Error saving privacy setting",
+                "Your privacy setting couldn't be saved. Please try again later.",
+                &err,
+                caller,
+            ),
+        };
+
+        Ok(response)
+    }
+}
+
+// Refer to the owner of an account, "Your" for the caller or a mention for anyone else
+fn owner(caller: &Caller, discord_id: UserId) -> String {
+    if discord_id == caller.id {
+        "Your".to_string()
+    } else {
+        format!("<@{}>'s", discord_id)
+    }
+}
@@ -0,0 +1,286 @@
+use crate::discord::arguments::{ArgError, CommandArgs};
+use crate::discord::commands::Command;
+use crate::discord::discord::{Caller, Handler};
+use crate::discord::response::Response;
+use crate::guild_settings::{GuildSettings, Setting, CONFIG_COMMAND};
+use serenity::async_trait;
+use serenity::builder::CreateCommandOption;
+use serenity::model::application::CommandOptionType;
+use serenity::model::permissions::Permissions;
+
+pub struct CacheStats;
+
+#[async_trait]
+impl Command for CacheStats {
+    fn name(&self) -> &'static str {
+        "cache-stats"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show how well the StackUp response cache is doing."
+    }
+
+    // Every server shares the cache, so no single server's admins get to see into it
+    fn owner_only(&self) -> bool {
+        true
+    }
+
+    async fn run(
+        &self,
+        handler: &Handler,
+        caller: &Caller,
+        _args: &CommandArgs<'_>,
+    ) -> Result<Response, ArgError> {
+        // List each endpoint's cache on its own line
+        let mut stats_message_details = String::from("**Response cache:**\n\n");
+        for (endpoint, stats) in handler.cache.stats() {
+            stats_message_details.push_str(&format!(
+                "**{}:** {} cached, {} hits, {} stale hits, {} misses\n",
+                endpoint, stats.entries, stats.hits, stats.stale_hits, stats.misses
+            ));
+        }
+        Ok(Response::new(stats_message_details, caller.colors.info))
+    }
+}
+
+pub struct CacheClear;
+
+#[async_trait]
+impl Command for CacheClear {
+    fn name(&self) -> &'static str {
+        "cache-clear"
+    }
+
+    fn description(&self) -> &'static str {
+        "Drop every cached StackUp response so the next commands fetch fresh data."
+    }
+
+    // Clearing the cache slows every server down, so only the owners get to
+    fn owner_only(&self) -> bool {
+        true
+    }
+
+    async fn run(
+        &self,
+        handler: &Handler,
+        caller: &Caller,
+        _args: &CommandArgs<'_>,
+    ) -> Result<Response, ArgError> {
+        let cleared = handler.cache.clear();
+        let clear_message_details = format!("Cleared **{}** cached responses.", cleared);
+        Ok(Response::new(clear_message_details, caller.colors.success))
+    }
+}
+
+pub struct Config;
+
+#[async_trait]
+impl Command for Config {
+    fn name(&self) -> &'static str {
+        CONFIG_COMMAND
+    }
+
+    fn description(&self) -> &'static str {
+        "View or change this server's settings."
+    }
+
+    fn usage(&self) -> &'static str {
+        "<get|set|reset> [setting] [value]"
+    }
+
+    fn arguments(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            (
+                "action",
+                "`get` to view the settings, `set` to change one or `reset` to restore its default.",
+            ),
+            (
+                "setting",
+                "One of `prefix`, `announcement-channel`, `allowed-channels`, \
+                 `disabled-commands` or `accent-color`. Optional for `get`.",
+            ),
+            ("value", "The new value, only for `set`."),
+        ]
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        &[
+            "config get",
+            "config set prefix ?",
+            "config set allowed-channels #bots #general",
+            "config set disabled-commands balance profile",
+            "config reset accent-color",
+            "/config action:set setting:prefix value:?",
+        ]
+    }
+
+    fn required_permissions(&self) -> Permissions {
+        Permissions::MANAGE_GUILD
+    }
+
+    fn options(&self) -> Vec<CreateCommandOption> {
+        let setting = Setting::ALL.into_iter().fold(
+            CreateCommandOption::new(CommandOptionType::String, "setting", "The setting"),
+            |option, setting| option.add_string_choice(setting.key(), setting.key()),
+        );
+        vec![
+            CreateCommandOption::new(CommandOptionType::String, "action", "What to do")
+                .required(true)
+                .add_string_choice("Get - view the settings", "get")
+                .add_string_choice("Set - change a setting", "set")
+                .add_string_choice("Reset - restore a setting's default", "reset"),
+            setting,
+            CreateCommandOption::new(CommandOptionType::String, "value", "The new value"),
+        ]
+    }
+
+    async fn run(
+        &self,
+        handler: &Handler,
+        caller: &Caller,
+        args: &CommandArgs<'_>,
+    ) -> Result<Response, ArgError> {
+        // Settings belong to a server, so there is nothing to change in DMs
+        let Some(guild_id) = caller.guild_id else {
+            return Ok(Response::new(
+                "Settings can only be changed in a server.",
+                caller.colors.warning,
+            ));
+        };
+
+        let action = args
+            .get(0, "action")
+            .ok_or_else(|| ArgError::missing("action"))?
+            .to_lowercase();
+        let setting: Option<Setting> =
+            args.parse(1, "setting", "one of the settings listed by `config get`")?;
+
+        let response = match action.as_str() {
+            "get" => match handler.guild_settings.get(guild_id).await {
+                Ok(settings) => {
+                    let settings_message_details = match setting {
+                        Some(setting) => describe(handler, &settings, setting),
+                        None => Setting::ALL
+                            .into_iter()
+                            .map(|setting| describe(handler, &settings, setting))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    };
+                    Response::new(settings_message_details, caller.colors.info)
+                        .title("Server settings")
+                }
+                // Let the user know if reading the settings fails
+                Err(err) => handler.error_response(
+                    "Error getting guild settings",
+                    "The server's settings couldn't be read. Please try again later.",
+                    &err,
+                    caller,
+                ),
+            },
+            "set" => {
+                let setting = setting.ok_or_else(|| ArgError::missing("setting"))?;
+                let value = args
+                    .rest(2, "value")
+                    .ok_or_else(|| ArgError::missing("value"))?;
+                let normalized = setting
+                    .normalize(&value)
+                    .ok_or_else(|| ArgError::invalid("value", &value, setting.expected()))?;
+                let normalized = match setting {
+                    Setting::DisabledCommands => command_names(handler, &normalized)?,
+                    _ => normalized,
+                };
+
+                // Save the setting and let the user know how it went
+                match handler
+                    .guild_settings
+                    .set(guild_id, setting, &normalized)
+                    .await
+                {
+                    Ok(()) => {
+                        let settings = handler.settings(Some(guild_id)).await;
+                        let set_message_details = format!(
+                            "**{}** is now {}.",
+                            setting,
+                            settings.describe(setting).unwrap_or(normalized)
+                        );
+                        Response::new(set_message_details, caller.colors.success)
+                    }
+                    // Let the user know if saving the setting fails
+                    Err(err) => handler.error_response(
+                        "Error saving guild setting",
+                        "The setting couldn't be saved. Please try again later.",
+                        &err,
+                        caller,
+                    ),
+                }
+            }
+            "reset" => {
+                let setting = setting.ok_or_else(|| ArgError::missing("setting"))?;
+
+                // Remove the setting and let the user know how it went
+                match handler.guild_settings.reset(guild_id, setting).await {
+                    Ok(true) => {
+                        let reset_message_details =
+                            format!("**{}** is back to its default.", setting);
+                        Response::new(reset_message_details, caller.colors.success)
+                    }
+                    Ok(false) => {
+                        let reset_message_details =
+                            format!("**{}** is already using its default.", setting);
+                        Response::new(reset_message_details, caller.colors.warning)
+                    }
+                    // Let the user know if removing the setting fails
+                    Err(err) => handler.error_response(
+                        "Error resetting guild setting",
+                        "The setting couldn't be reset. Please try again later.",
+                        &err,
+                        caller,
+                    ),
+                }
+            }
+            _ => {
+                return Err(ArgError::invalid(
+                    "action",
+                    action,
+                    "`get`, `set` or `reset`",
+                ))
+            }
+        };
+
+        Ok(response)
+    }
+}
+
+// Show a setting's value in the server, or what it falls back to when it isn't set
+fn describe(handler: &Handler, settings: &GuildSettings, setting: Setting) -> String {
+    let value = settings.describe(setting).unwrap_or_else(|| {
+        let default = match setting {
+            Setting::Prefix => format!("`{}`", handler.config.command_prefix),
+            Setting::AnnouncementChannel => "none".to_string(),
+            Setting::AllowedChannels => "every channel".to_string(),
+            Setting::DisabledCommands => "none".to_string(),
+            Setting::AccentColor => "the default colors".to_string(),
+        };
+        format!("{} *(default)*", default)
+    });
+    format!("**{}:** {}", setting, value)
+}
+
+// Check the commands to turn off exist, storing them under their main names. The `config`
+// command can't be turned off.
+fn command_names(handler: &Handler, names: &str) -> Result<String, ArgError> {
+    let names: Vec<&str> = names
+        .split(',')
+        .map(|name| {
+            handler
+                .commands
+                .find(name)
+                .map(|command| command.name())
+                .filter(|name| *name != CONFIG_COMMAND)
+                .ok_or_else(|| {
+                    ArgError::invalid("value", name, "the names of commands other than `config`")
+                })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(names.join(","))
+}
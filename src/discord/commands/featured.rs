@@ -0,0 +1,335 @@
+use crate::discord::arguments::{ArgError, CommandArgs};
+use crate::discord::commands::Command;
+use crate::discord::discord::{Caller, Handler};
+use crate::discord::render;
+use crate::discord::response::Response;
+use crate::subscriptions::Topic;
+use serenity::async_trait;
+use serenity::builder::CreateCommandOption;
+use serenity::model::application::CommandOptionType;
+
+pub struct Campaigns;
+
+#[async_trait]
+impl Command for Campaigns {
+    fn name(&self) -> &'static str {
+        "campaigns"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["get-campaigns"]
+    }
+
+    fn description(&self) -> &'static str {
+        "View featured campaigns."
+    }
+
+    fn usage(&self) -> &'static str {
+        "[page]"
+    }
+
+    fn arguments(&self) -> &'static [(&'static str, &'static str)] {
+        PAGE_ARGUMENTS
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        &["campaigns", "campaigns 2", "/campaigns page:2"]
+    }
+
+    fn options(&self) -> Vec<CreateCommandOption> {
+        vec![page_option()]
+    }
+
+    async fn run(
+        &self,
+        handler: &Handler,
+        caller: &Caller,
+        args: &CommandArgs<'_>,
+    ) -> Result<Response, ArgError> {
+        let page = start_page(args)?;
+
+        // Fetch featured campaigns and handle the result
+        let response = match handler.api.get_featured_campaigns().await {
+            Ok(campaigns) => render::campaigns(&campaigns, &caller.colors)
+                .paginate(handler.config.embed.page_size)
+                .start_at(page),
+            // Let the user know if fetching campaigns fails
+            Err(err) => {
+                handler.api_error_response("Error getting featured campaigns", &err, caller)
+            }
+        };
+
+        Ok(response)
+    }
+}
+
+pub struct Pathways;
+
+#[async_trait]
+impl Command for Pathways {
+    fn name(&self) -> &'static str {
+        "pathways"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["get-pathways"]
+    }
+
+    fn description(&self) -> &'static str {
+        "View various resources for various tracks."
+    }
+
+    fn usage(&self) -> &'static str {
+        "[page]"
+    }
+
+    fn arguments(&self) -> &'static [(&'static str, &'static str)] {
+        PAGE_ARGUMENTS
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        &["pathways", "pathways 2", "/pathways page:2"]
+    }
+
+    fn options(&self) -> Vec<CreateCommandOption> {
+        vec![page_option()]
+    }
+
+    async fn run(
+        &self,
+        handler: &Handler,
+        caller: &Caller,
+        args: &CommandArgs<'_>,
+    ) -> Result<Response, ArgError> {
+        let page = start_page(args)?;
+
+        // Fetch featured pathways and handle the result
+        let response = match handler.api.get_pathways().await {
+            Ok(pathways) => render::pathways(&pathways, &caller.colors)
+                .paginate(handler.config.embed.page_size)
+                .start_at(page),
+            // Let the user know if fetching pathways fails
+            Err(err) => handler.api_error_response("Error getting featured pathways", &err, caller),
+        };
+
+        Ok(response)
+    }
+}
+
+pub struct Hackathons;
+
+#[async_trait]
+impl Command for Hackathons {
+    fn name(&self) -> &'static str {
+        "hackathons"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["get-hackathons"]
+    }
+
+    fn description(&self) -> &'static str {
+        "See upcoming hackathons."
+    }
+
+    fn usage(&self) -> &'static str {
+        "[page]"
+    }
+
+    fn arguments(&self) -> &'static [(&'static str, &'static str)] {
+        PAGE_ARGUMENTS
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        &["hackathons", "hackathons 2", "/hackathons page:2"]
+    }
+
+    fn options(&self) -> Vec<CreateCommandOption> {
+        vec![page_option()]
+    }
+
+    async fn run(
+        &self,
+        handler: &Handler,
+        caller: &Caller,
+        args: &CommandArgs<'_>,
+    ) -> Result<Response, ArgError> {
+        let page = start_page(args)?;
+
+        // Fetch upcoming hackathons and handle the result
+        let response = match handler.api.get_hackathons().await {
+            Ok(hackathons) => render::hackathons(&hackathons, &caller.colors)
+                .paginate(handler.config.embed.page_size)
+                .start_at(page),
+            // Let the user know if fetching hackathons fails
+            Err(err) => {
+                handler.api_error_response("Error getting upcoming hackathons", &err, caller)
+            }
+        };
+
+        Ok(response)
+    }
+}
+
+pub struct Subscribe;
+
+#[async_trait]
+impl Command for Subscribe {
+    fn name(&self) -> &'static str {
+        "subscribe"
+    }
+
+    fn description(&self) -> &'static str {
+        "Get a DM whenever a new campaign, hackathon or pathway is featured."
+    }
+
+    fn usage(&self) -> &'static str {
+        "<campaigns|hackathons|pathways>"
+    }
+
+    fn arguments(&self) -> &'static [(&'static str, &'static str)] {
+        &[(
+            "topic",
+            "What to hear about: `campaigns`, `hackathons` or `pathways`.",
+        )]
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        &["subscribe campaigns", "/subscribe topic:hackathons"]
+    }
+
+    fn options(&self) -> Vec<CreateCommandOption> {
+        vec![topic_option("What to hear about")]
+    }
+
+    async fn run(
+        &self,
+        handler: &Handler,
+        caller: &Caller,
+        args: &CommandArgs<'_>,
+    ) -> Result<Response, ArgError> {
+        let topic = topic(args)?;
+
+        // Save the subscription and let the user know how it went
+        let response = match handler.store.subscribe(caller.id, topic).await {
+            Ok(added) => {
+                let subscribe_message_details = if added {
+                    format!(
+                        "You'll get a DM whenever there are new {}. Make sure you allow direct \
+                         messages from server members.",
+                        topic
+                    )
+                } else {
+                    format!("You're already subscribed to new {}.", topic)
+                };
+                Response::new(subscribe_message_details, caller.colors.success)
+            }
+            // Let the user know if saving the subscription fails
+            Err(err) => handler.error_response(
+                "Error saving subscription",
+                "Your subscription couldn't be saved. Please try again later.",
+                &err,
+                caller,
+            ),
+        };
+
+        Ok(response)
+    }
+}
+
+pub struct Unsubscribe;
+
+#[async_trait]
+impl Command for Unsubscribe {
+    fn name(&self) -> &'static str {
+        "unsubscribe"
+    }
+
+    fn description(&self) -> &'static str {
+        "Stop getting DMs about new campaigns, hackathons or pathways."
+    }
+
+    fn usage(&self) -> &'static str {
+        "<campaigns|hackathons|pathways>"
+    }
+
+    fn arguments(&self) -> &'static [(&'static str, &'static str)] {
+        &[(
+            "topic",
+            "What to stop hearing about: `campaigns`, `hackathons` or `pathways`.",
+        )]
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        &["unsubscribe campaigns", "/unsubscribe topic:hackathons"]
+    }
+
+    fn options(&self) -> Vec<CreateCommandOption> {
+        vec![topic_option("What to stop hearing about")]
+    }
+
+    async fn run(
+        &self,
+        handler: &Handler,
+        caller: &Caller,
+        args: &CommandArgs<'_>,
+    ) -> Result<Response, ArgError> {
+        let topic = topic(args)?;
+
+        // Remove the subscription and let the user know how it went
+        let response = match handler.store.unsubscribe(caller.id, topic).await {
+            Ok(true) => {
+                let unsubscribe_message_details =
+                    format!("You'll no longer get DMs about new {}.", topic);
+                Response::new(unsubscribe_message_details, caller.colors.success)
+            }
+            Ok(false) => {
+                let unsubscribe_message_details =
+                    format!("You aren't subscribed to new {}.", topic);
+                Response::new(unsubscribe_message_details, caller.colors.warning)
+            }
+            // Let the user know if removing the subscription fails
+            Err(err) => handler.error_response(
+                "Error removing subscription",
+                "Your subscription couldn't be removed. Please try again later.",
+                &err,
+                caller,
+            ),
+        };
+
+        Ok(response)
+    }
+}
+
+// The featured lists can be opened on any of their pages
+const PAGE_ARGUMENTS: &[(&str, &str)] = &[("page", "Optional. The page to start on.")];
+
+// The slash command option choosing the page to start on
+fn page_option() -> CreateCommandOption {
+    CreateCommandOption::new(CommandOptionType::Integer, "page", "The page to start on")
+        .min_int_value(1)
+}
+
+// Read the page a featured list starts on, counting from one like the page footers do
+fn start_page(args: &CommandArgs<'_>) -> Result<usize, ArgError> {
+    const EXPECTED: &str = "a page number like `2`";
+    match args.parse::<usize>(0, "page", EXPECTED)? {
+        Some(0) => Err(ArgError::invalid("page", 0, EXPECTED)),
+        Some(page) => Ok(page - 1),
+        None => Ok(0),
+    }
+}
+
+// Read the topic to (un)subscribe from
+fn topic(args: &CommandArgs<'_>) -> Result<Topic, ArgError> {
+    args.parse(0, "topic", "`campaigns`, `hackathons` or `pathways`")?
+        .ok_or_else(|| ArgError::missing("topic"))
+}
+
+// The slash command option choosing a topic
+fn topic_option(description: &str) -> CreateCommandOption {
+    Topic::ALL.into_iter().fold(
+        CreateCommandOption::new(CommandOptionType::String, "topic", description).required(true),
+        |option, topic| option.add_string_choice(topic.as_str(), topic.as_str()),
+    )
+}
@@ -0,0 +1,161 @@
+use crate::discord::arguments::{ArgError, CommandArgs};
+use crate::discord::commands::{command_help, is_available, Command};
+use crate::discord::discord::{Caller, Handler};
+use crate::discord::messages::INFO_MESSAGE;
+use crate::discord::response::Response;
+use serenity::async_trait;
+use serenity::builder::CreateCommandOption;
+use serenity::model::application::CommandOptionType;
+
+pub struct Help;
+
+#[async_trait]
+impl Command for Help {
+    fn name(&self) -> &'static str {
+        "help"
+    }
+
+    fn description(&self) -> &'static str {
+        "Get a list of all commands."
+    }
+
+    fn usage(&self) -> &'static str {
+        "[command]"
+    }
+
+    fn arguments(&self) -> &'static [(&'static str, &'static str)] {
+        &[(
+            "command",
+            "Optional. The command to show detailed help for.",
+        )]
+    }
+
+    fn examples(&self) -> &'static [&'static str] {
+        &["help", "help link", "/help command:balance"]
+    }
+
+    // The help only lists the commands the caller is allowed to run
+    fn uses_permissions(&self) -> bool {
+        true
+    }
+
+    fn options(&self) -> Vec<CreateCommandOption> {
+        vec![CreateCommandOption::new(
+            CommandOptionType::String,
+            "command",
+            "Get details about a single command",
+        )
+        .set_autocomplete(true)]
+    }
+
+    async fn autocomplete(&self, handler: &Handler, caller: &Caller, partial: &str) -> Vec<String> {
+        // Suggest the commands the caller can run in the server whose names start with what they
+        // have typed so far
+        let settings = handler.settings(caller.guild_id).await;
+        let partial = partial
+            .trim_start_matches('/')
+            .trim_start_matches(caller.prefix.as_str())
+            .to_lowercase();
+        handler
+            .commands
+            .iter()
+            .filter(|command| is_available(*command, caller, &settings))
+            .map(|command| command.name())
+            .filter(|name| name.starts_with(&partial))
+            .map(String::from)
+            .collect()
+    }
+
+    async fn run(
+        &self,
+        handler: &Handler,
+        caller: &Caller,
+        args: &CommandArgs<'_>,
+    ) -> Result<Response, ArgError> {
+        // Only show the commands the caller is allowed to run and the server hasn't turned off
+        let settings = handler.settings(caller.guild_id).await;
+
+        // Send the full help message as an embed when no command is given
+        let Some(command) = args.get(0, "command") else {
+            let help_message = handler.commands.help_message(caller, &settings);
+            return Ok(Response::new(help_message, caller.colors.help));
+        };
+
+        // Otherwise show the detailed help for the requested command
+        let name = command
+            .trim_start_matches('/')
+            .trim_start_matches(caller.prefix.as_str());
+        let help_message_details = match handler
+            .commands
+            .find(name)
+            .filter(|command| is_available(*command, caller, &settings))
+        {
+            Some(command) => command_help(command, &caller.prefix),
+            None => format!(
+                "Unknown command `{}`. Use `/help` to see every command.",
+                command
+            ),
+        };
+        Ok(Response::new(help_message_details, caller.colors.help))
+    }
+}
+
+pub struct Info;
+
+#[async_trait]
+impl Command for Info {
+    fn name(&self) -> &'static str {
+        "info"
+    }
+
+    fn description(&self) -> &'static str {
+        "Learn more about what I can do."
+    }
+
+    async fn run(
+        &self,
+        _handler: &Handler,
+        caller: &Caller,
+        _args: &CommandArgs<'_>,
+    ) -> Result<Response, ArgError> {
+        // Send the info message as an embed
+        Ok(Response::new(INFO_MESSAGE, caller.colors.info))
+    }
+}
+
+pub struct Calendar;
+
+#[async_trait]
+impl Command for Calendar {
+    fn name(&self) -> &'static str {
+        "calendar"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["get-calendar"]
+    }
+
+    fn description(&self) -> &'static str {
+        "Access the StackUp calendar."
+    }
+
+    async fn run(
+        &self,
+        _handler: &Handler,
+        caller: &Caller,
+        _args: &CommandArgs<'_>,
+    ) -> Result<Response, ArgError> {
+        // Define the calendar link and image URL
+        let calendar_link = "https://stackup.dev/calendar";
+        const CALENDAR_IMG_URL: &str = "https://i.imgur.com/hxxfDQ9.png";
+        // Define the calendar message
+        const CALENDAR_MESSAGE: &str = "
+            Check out the latest activities happening on the **StackUp platform**, the platform for developers, where you can learn, earn and create projects.
+        ";
+        // Format the calendar details into a message
+        let calendar_details =
+            format!("{} [Monthly Calendar]({})", CALENDAR_MESSAGE, calendar_link);
+        // Send the formatted message as an embed with an image
+        Ok(Response::new(calendar_details, caller.colors.calendar).image(CALENDAR_IMG_URL))
+    }
+}
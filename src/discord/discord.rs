@@ -1,319 +1,664 @@
-use reqwest::Client as ReqwestClient;
+use crate::api::{StackUpApi, StackUpApiError};
+use crate::cache::ApiCache;
+use crate::config::{Config, EmbedColors};
+use crate::cooldowns::{Cooldown, Cooldowns};
+use crate::discord::arguments::{parse_args, ArgError, CommandArgs};
+use crate::discord::commands::{command_signature, is_permitted, Command, CommandRegistry};
+use crate::discord::messages::NOT_LINKED_MESSAGE;
+use crate::discord::paginator::Paginator;
+use crate::discord::responder::{DiscordResponder, Responder};
+use crate::discord::response::Response;
+use crate::discord::slash::register_slash_commands;
+use crate::guild_settings::{GuildSettings, GuildSettingsCache, Setting};
+use crate::links::AccountLink;
+use crate::logging::command_span;
+use crate::store::Store;
+use crate::util::with_loading_indicator;
 use serenity::async_trait;
-use serenity::builder::{CreateEmbed, CreateEmbedAuthor, CreateMessage};
-use serenity::model::colour::Colour;
+use serenity::builder::{
+    CreateAutocompleteResponse, CreateInteractionResponse, CreateInteractionResponseMessage,
+};
 use serenity::model::prelude::*;
 use serenity::prelude::*;
-use crate::api::{
-    get_featured_campaigns, get_hackathons, get_pathways, get_user_balance, get_user_details,
-    get_user_progress,
-};
-use crate::discord::messages::{HELP_MESSAGE, INFO_MESSAGE};
-use crate::util::show_loading_animation;
-
-// Creating a function to display embedded messages
-pub async fn send_embed_message(
-    command_name: &str,
-    message_details: &str,
-    color: Colour,
-    ctx: &Context,
-    msg: &Message,
-    image_url: Option<&str>,
-) {
-    // Check if the message content matches the command name
-    if msg.content == command_name {
-        // Create an author for the embed message
-        let author = CreateEmbedAuthor::new("Stack-Buddy");
-
-        // Initialize the embed message with author, description, thumbnail, timestamp, and color
-        let mut embed = CreateEmbed::default()
-            .author(author)
-            .description(message_details)
-            .thumbnail("https://i.imgur.com/xRT3jsZ.png")
-            .timestamp(Timestamp::now())
-            .color(color);
-
-        // If an image URL is provided, add it to the embed
-        if let Some(url) = image_url {
-            embed = embed.image(url);
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, Instrument};
+
+// Where a command was invoked from, so the reply can be sent back the same way
+pub enum Invocation<'a> {
+    Prefix(&'a Message),
+    Slash(&'a CommandInteraction),
+}
+
+impl Invocation<'_> {
+    // Get the ID of the invoking message or interaction, used to correlate replies with logs
+    pub fn id(&self) -> u64 {
+        match self {
+            Invocation::Prefix(msg) => msg.id.get(),
+            Invocation::Slash(command) => command.id.get(),
+        }
+    }
+
+    // Get the invoking member's permissions in the guild, or none outside of a guild
+    pub async fn member_permissions(&self, ctx: &Context) -> Permissions {
+        match self {
+            // Discord sends the member's permissions along with the slash command
+            Invocation::Slash(command) => command
+                .member
+                .as_ref()
+                .and_then(|member| member.permissions)
+                .unwrap_or_else(Permissions::empty),
+            // Prefix commands have to work them out from the guild's roles
+            Invocation::Prefix(msg) => {
+                let (Some(guild_id), Some(member)) = (msg.guild_id, msg.member.as_deref()) else {
+                    return Permissions::empty();
+                };
+
+                match guild_id.to_partial_guild(&ctx.http).await {
+                    Ok(guild) => guild.partial_member_permissions(msg.author.id, member),
+                    Err(why) => {
+                        error!(error = ?why, "Error getting guild permissions");
+                        Permissions::empty()
+                    }
+                }
+            }
+        }
+    }
+
+    // Get the guild the command was invoked in, or None in DMs
+    pub fn guild_id(&self) -> Option<GuildId> {
+        match self {
+            Invocation::Prefix(msg) => msg.guild_id,
+            Invocation::Slash(command) => command.guild_id,
         }
+    }
 
-        // Create the message with the embed
-        let message = CreateMessage::default().embed(embed);
+    // Get the channel the command was invoked in
+    pub fn channel_id(&self) -> ChannelId {
+        match self {
+            Invocation::Prefix(msg) => msg.channel_id,
+            Invocation::Slash(command) => command.channel_id,
+        }
+    }
 
-        if let Err(why) = msg.channel_id.send_message(&ctx.http, message).await {
-            println!("Error sending message: {:?}", why);
+    // Get the user who invoked the command
+    pub fn author(&self) -> &User {
+        match self {
+            Invocation::Prefix(msg) => &msg.author,
+            Invocation::Slash(command) => &command.user,
         }
     }
 }
 
+// Who ran a command, which is all a command needs to know about where it was invoked from
+pub struct Caller {
+    pub id: UserId,
+    // The guild and channel the command was run in; there is no guild in DMs
+    pub guild_id: Option<GuildId>,
+    pub channel_id: ChannelId,
+    // The caller's permissions in the guild; only looked up for commands that use them
+    pub permissions: Permissions,
+    // Whether the caller is one of the bot's owners
+    pub is_owner: bool,
+    // The ID of the invoking message or interaction, used to correlate replies with logs
+    pub correlation_id: u64,
+    // The prefix commands start with where the command was run
+    pub prefix: String,
+    // The embed colors, with the guild's accent color applied
+    pub colors: EmbedColors,
+}
+
+// Discord only accepts up to 25 autocomplete choices
+const MAX_AUTOCOMPLETE_CHOICES: usize = 25;
+
 pub struct Handler {
-    pub http_client: ReqwestClient,
-    pub base_url: String,
+    // The StackUp API, answered from the cache where possible
+    pub api: Arc<dyn StackUpApi>,
+    // The same cache, for the commands that inspect or clear it
+    pub cache: Arc<ApiCache>,
+    // Account links, subscriptions and everything else kept between restarts
+    pub store: Arc<dyn Store>,
+    // Each guild's settings, kept in memory in front of the store
+    pub guild_settings: GuildSettingsCache,
+    // How often each member and channel can use each command
+    pub cooldowns: Cooldowns,
+    pub commands: CommandRegistry,
+    pub config: Config,
 }
 
 #[async_trait]
 impl EventHandler for Handler {
     async fn message(&self, ctx: Context, msg: Message) {
-        // Trim the message content to remove any leading/trailing whitespace
-        let content = msg.content.trim();
-        // Match the trimmed content to the corresponding command handler
-        match content {
-            "!get-username" => self.handle_get_username(&ctx, &msg).await,
-            "!help" => self.handle_help(&ctx, &msg).await,
-            "!info" => self.handle_info(&ctx, &msg).await,
-            "!get-balance" => self.handle_get_balance(&ctx, &msg).await,
-            "!get-profile" => self.handle_get_profile(&ctx, &msg).await,
-            "!get-campaigns" => self.handle_get_campaigns(&ctx, &msg).await,
-            "!get-pathways" => self.handle_get_pathways(&ctx, &msg).await,
-            "!get-hackathons" => self.handle_get_hackathons(&ctx, &msg).await,
-            "!get-calendar" => self.handle_get_calendar(&ctx, &msg).await,
-            _ => {}
+        // Ignore messages entirely when the prefix commands are turned off
+        if !self.config.prefix_commands {
+            return;
         }
-    }
 
-    async fn ready(&self, _: Context, ready: Ready) {
-        // Print a message when the bot is connected
-        println!("{} is connected!", ready.user.name);
+        // Stay quiet in guilds the bot isn't allowed to answer in
+        if !self.config.is_guild_allowed(msg.guild_id) {
+            return;
+        }
+
+        // Only messages starting with the guild's command prefix are commands
+        let settings = self.settings(msg.guild_id).await;
+        let prefix = settings.prefix(&self.config.command_prefix);
+        let Some(content) = msg.content.strip_prefix(prefix) else {
+            return;
+        };
+
+        // Split the message content into the command name and the rest of the arguments
+        let content = content.trim_start();
+        let (name, rest) = content
+            .split_once(char::is_whitespace)
+            .unwrap_or((content, ""));
+
+        // Find the command with that name or alias
+        let Some(command) = self.commands.find(name) else {
+            return;
+        };
+
+        // Stay quiet in channels the guild doesn't allow commands in
+        if !settings.is_allowed_in(command.name(), msg.channel_id) {
+            return;
+        }
+
+        let inv = Invocation::Prefix(&msg);
+        self.traced(command, &inv, async {
+            // Parse its arguments and run it, showing the usage if they can't be parsed
+            match parse_args(rest, command.switches()) {
+                Ok(args) => {
+                    let args = CommandArgs::Prefix(args);
+                    self.run_command(command, &ctx, &inv, &args).await;
+                }
+                Err(err) => {
+                    debug!(error = ?err, "Couldn't parse arguments");
+                    let response = self.usage_hint(command, &err, prefix);
+                    let responder = self.responder(&ctx, &inv, None);
+                    if let Err(why) = responder.reply(&response).await {
+                        error!(error = ?why, "Error sending usage hint");
+                    }
+                }
+            }
+        })
+        .await;
     }
-}
 
-impl Handler {
-    async fn handle_get_username(&self, ctx: &Context, msg: &Message) {
-        // Show loading animation
-        let loading_msg = show_loading_animation(ctx, msg, "Loading...").await;
-
-        let user_id: i32 = 1;
-        // Check if the user ID is valid
-        if user_id > 0 {
-            // Fetch user details and handle the result
-            match get_user_details(&self.http_client, &self.base_url, user_id).await {
-                Ok(user) => {
-                    // Format the user details into a message
-                    let message_details = format!(
-                        "ID: {}\nUsername: {}\nNationality: {}\nCareer Level: {}\nRole: {}\nTech Stacks: {}",
-                        user.id, user.username, user.nationality, user.career_level, user.role, user.tech_stack
-                    );
-                    // Send the formatted message as an embed
-                    send_embed_message(
-                        "!get-username",
-                        &message_details,
-                        Colour::DARK_BLUE,
-                        &ctx,
-                        &msg,
-                        None,
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        // Stay quiet in guilds the bot isn't allowed to answer in
+        let guild_id = match &interaction {
+            Interaction::Command(command) => command.guild_id,
+            Interaction::Autocomplete(autocomplete) => autocomplete.guild_id,
+            _ => None,
+        };
+        if !self.config.is_guild_allowed(guild_id) {
+            return;
+        }
+
+        match interaction {
+            Interaction::Command(command) => {
+                // Find the registered command with that name
+                let Some(registered) = self.commands.find(&command.data.name) else {
+                    return;
+                };
+
+                let inv = Invocation::Slash(&command);
+                self.traced(registered, &inv, async {
+                    let args = CommandArgs::Slash(&command);
+                    self.run_command(registered, &ctx, &inv, &args).await;
+                })
+                .await;
+            }
+            Interaction::Autocomplete(autocomplete) => {
+                // Ask the command for suggestions matching what has been typed so far
+                let Some(registered) = self.commands.find(&autocomplete.data.name) else {
+                    return;
+                };
+
+                let partial = autocomplete
+                    .data
+                    .autocomplete()
+                    .map(|option| option.value)
+                    .unwrap_or_default();
+                // Discord sends the member's permissions along with the interaction
+                let permissions = autocomplete
+                    .member
+                    .as_ref()
+                    .and_then(|member| member.permissions)
+                    .unwrap_or_else(Permissions::empty);
+                let caller = self
+                    .caller(
+                        autocomplete.user.id,
+                        autocomplete.guild_id,
+                        autocomplete.channel_id,
+                        permissions,
+                        autocomplete.id.get(),
                     )
                     .await;
-                }
-                Err(err) => {
-                    // Print an error message if fetching user details fails
-                    println!("Error getting user details: {:?}", err);
+                let choices = registered
+                    .autocomplete(self, &caller, partial)
+                    .await
+                    .into_iter()
+                    .take(MAX_AUTOCOMPLETE_CHOICES)
+                    .fold(CreateAutocompleteResponse::new(), |response, choice| {
+                        response.add_string_choice(choice.clone(), choice)
+                    });
+                let response = CreateInteractionResponse::Autocomplete(choices);
+                if let Err(why) = autocomplete.create_response(&ctx.http, response).await {
+                    error!(error = ?why, "Error sending autocomplete choices");
                 }
             }
-        } else {
-            // Print an error message if the user ID is invalid
-            println!("Invalid user ID");
+            _ => {}
         }
     }
 
-    async fn handle_help(&self, ctx: &Context, msg: &Message) {
-        // Send the help message as an embed
-        send_embed_message("!help", HELP_MESSAGE, Colour::DARK_GREEN, &ctx, &msg, None).await;
+    async fn ready(&self, ctx: Context, ready: Ready) {
+        // Log a message when the bot is connected
+        info!("{} is connected!", ready.user.name);
+
+        // Register the slash commands so they show up in Discord
+        match register_slash_commands(&ctx, self.config.guild_id, self.commands.slash_commands())
+            .await
+        {
+            Ok(commands) => info!("Registered {} slash commands", commands.len()),
+            Err(why) => error!(error = ?why, "Error registering slash commands"),
+        }
     }
+}
 
-    async fn handle_info(&self, ctx: &Context, msg: &Message) {
-        // Send the info message as an embed
-        send_embed_message("!info", INFO_MESSAGE, Colour::RED, &ctx, &msg, None).await;
+impl Handler {
+    // Handle a command inside its span, logging how long it took once it has been answered
+    async fn traced(&self, command: &dyn Command, inv: &Invocation<'_>, handle: impl Future) {
+        let span = command_span(
+            command.name(),
+            inv.author().id,
+            inv.guild_id(),
+            inv.channel_id(),
+            inv.id(),
+        );
+        let started = Instant::now();
+        handle.instrument(span.clone()).await;
+        span.record("latency_ms", started.elapsed().as_millis() as u64);
+        span.in_scope(|| info!("Handled command"));
     }
 
-    async fn handle_get_balance(&self, ctx: &Context, msg: &Message) {
-        // Show loading animation
-        let loading_msg = show_loading_animation(ctx, msg, "Loading...").await;
-
-        let user_id: i32 = 1;
-        // Fetch user balance and handle the result
-        match get_user_balance(&self.http_client, &self.base_url, user_id).await {
-            Ok(balance) => {
-                // Format the balance details into a message
-                let balance_messsage_details =
-                    format!("Your StackUp balance is: ${}", balance.current_balance);
-                // Send the formatted message as an embed
-                send_embed_message(
-                    "!get-balance",
-                    &balance_messsage_details,
-                    Colour::DARK_PURPLE,
-                    &ctx,
-                    &msg,
-                    None,
-                )
-                .await;
+    // Run a command behind the loading indicator and send its response back where it came from
+    async fn run_command(
+        &self,
+        command: &dyn Command,
+        ctx: &Context,
+        inv: &Invocation<'_>,
+        args: &CommandArgs<'_>,
+    ) {
+        // Only look up the caller's permissions when the command cares about them
+        let permissions =
+            if command.required_permissions().is_empty() && !command.uses_permissions() {
+                Permissions::empty()
+            } else {
+                inv.member_permissions(ctx).await
+            };
+        let caller = self
+            .caller(
+                inv.author().id,
+                inv.guild_id(),
+                inv.channel_id(),
+                permissions,
+                inv.id(),
+            )
+            .await;
+
+        // Tell members who use commands too often to slow down, once
+        match self.cooldown(command, &caller).await {
+            Cooldown::Ready => {}
+            Cooldown::Notify(wait) => {
+                debug!("Asking member to slow down");
+                self.send_cooldown_notice(ctx, inv, wait).await;
+                return;
             }
-            Err(err) => {
-                // Print an error message if fetching user balance fails
-                println!("Error getting user balance: {:?}", err);
+            Cooldown::Silent => {
+                debug!("Dropping command during cooldown");
+                return;
             }
         }
-    }
 
-    async fn handle_get_profile(&self, ctx: &Context, msg: &Message) {
-        // Show loading animation
-        let loading_msg = show_loading_animation(ctx, msg, "Loading...").await;
-
-        let user_id: i32 = 1;
-        // Fetch user profile and handle the result
-        match get_user_progress(&self.http_client, &self.base_url, user_id).await {
-            Ok(profile) => {
-                // Format the profile details into a message
-                let profile_message_details = format!(
-                    "Submissions: {}\nSubmitted: {}\nRewarded: {}\nTotal Earnings: ${}",
-                    profile.submissions,
-                    profile.submitted,
-                    profile.rewarded,
-                    profile.total_quest_earings
-                );
-                // Send the formatted message as an embed
-                send_embed_message(
-                    "!get-profile",
-                    &profile_message_details,
-                    Colour::DARK_GOLD,
-                    &ctx,
-                    &msg,
-                    None,
-                )
-                .await;
+        // Acknowledge slash commands straight away, the reply is edited in once it's ready
+        if let Invocation::Slash(interaction) = inv {
+            if let Err(why) = interaction.defer(&ctx.http).await {
+                error!(error = ?why, "Error deferring slash command");
+                return;
             }
-            Err(err) => {
-                // Print an error message if fetching user profile fails
-                println!("Error getting user profile: {:?}", err);
+        }
+
+        let (response, loading_msg) = self
+            .with_loading(ctx, inv, &caller, async {
+                // Keep track of how often each command is used
+                if let Err(why) = self
+                    .store
+                    .record_command(command.name(), caller.id, inv.guild_id())
+                    .await
+                {
+                    error!(error = ?why, "Error recording command usage");
+                }
+                self.respond(command, &caller, args).await
+            })
+            .await;
+
+        let responder = self.responder(ctx, inv, loading_msg);
+        self.send_response(&responder, inv.guild_id(), &response)
+            .await;
+    }
+
+    // Describe who ran a command and where, with the settings of the guild it was run in
+    pub async fn caller(
+        &self,
+        id: UserId,
+        guild_id: Option<GuildId>,
+        channel_id: ChannelId,
+        permissions: Permissions,
+        correlation_id: u64,
+    ) -> Caller {
+        let settings = self.settings(guild_id).await;
+        let colors = match settings.accent_color {
+            Some(accent) => self.config.embed.colors.with_accent(accent),
+            None => self.config.embed.colors.clone(),
+        };
+        Caller {
+            id,
+            guild_id,
+            channel_id,
+            permissions,
+            is_owner: self.config.owners.contains(&id),
+            correlation_id,
+            prefix: settings.prefix(&self.config.command_prefix).to_string(),
+            colors,
+        }
+    }
+
+    // Get a guild's settings, or the defaults in DMs or when they can't be read
+    pub async fn settings(&self, guild_id: Option<GuildId>) -> Arc<GuildSettings> {
+        let Some(guild_id) = guild_id else {
+            return Arc::default();
+        };
+        match self.guild_settings.get(guild_id).await {
+            Ok(settings) => settings,
+            Err(why) => {
+                error!(guild = guild_id.get(), error = ?why, "Error getting guild settings");
+                Arc::default()
             }
         }
     }
 
-    async fn handle_get_campaigns(&self, ctx: &Context, msg: &Message) {
-        // Show loading animation
-        let loading_msg = show_loading_animation(ctx, msg, "Loading...").await;
-
-        // Fetch featured campaigns and handle the result
-        match get_featured_campaigns(&self.http_client, &self.base_url).await {
-            Ok(campaigns) => {
-                // Initialize the campaign message details
-                let mut campaign_message_details = String::from("**Featured campaigns:** \n\n");
-                // Append each campaign's details to the message
-                for campaign in campaigns {
-                    campaign_message_details.push_str(&format!(
-                        "**Title:** {}\n**Subtitle:** {}\n**Quest Count:** {}\n\n",
-                        campaign.title, campaign.sub_title, campaign.quest_count
-                    ));
+    // Work out a command's response, first checking the guild allows it and the caller has the
+    // permissions it requires
+    pub async fn respond(
+        &self,
+        command: &dyn Command,
+        caller: &Caller,
+        args: &CommandArgs<'_>,
+    ) -> Response {
+        if let Some(refusal) = self.refusal(command, caller).await {
+            return refusal;
+        }
+
+        match command.run(self, caller, args).await {
+            Ok(response) => response,
+            Err(err) => self.usage_hint(command, &err, &caller.prefix),
+        }
+    }
+
+    // Explain why a command can't be run, if the guild doesn't allow it or the caller lacks the
+    // permissions it requires
+    async fn refusal(&self, command: &dyn Command, caller: &Caller) -> Option<Response> {
+        let settings = self.settings(caller.guild_id).await;
+        if settings.is_disabled(command.name()) {
+            let disabled_message_details = format!(
+                "`{}{}` is turned off in this server.",
+                caller.prefix,
+                command.name()
+            );
+            return Some(Response::new(
+                disabled_message_details,
+                self.config.embed.colors.warning,
+            ));
+        }
+        if !settings.is_allowed_in(command.name(), caller.channel_id) {
+            let channel_message_details = format!(
+                "Commands can only be used in {} in this server.",
+                settings
+                    .describe(Setting::AllowedChannels)
+                    .unwrap_or_default()
+            );
+            return Some(Response::new(
+                channel_message_details,
+                self.config.embed.colors.warning,
+            ));
+        }
+
+        if command.owner_only() && !caller.is_owner {
+            return Some(Response::new(
+                "Only the bot's owners can use this command.",
+                self.config.embed.colors.warning,
+            ));
+        }
+        let required = command.required_permissions();
+        if !required.is_empty() && !is_permitted(command, caller.permissions) {
+            let permission_message_details = format!(
+                "You need the **{}** permission to use this command.",
+                required
+            );
+            return Some(Response::new(
+                permission_message_details,
+                self.config.embed.colors.warning,
+            ));
+        }
+        None
+    }
+
+    // Check whether the caller can use a command again yet. Commands that are refused anyway
+    // don't use up the cooldowns, so a member can't lock themselves out of the ones they can use.
+    pub async fn cooldown(&self, command: &dyn Command, caller: &Caller) -> Cooldown {
+        if self.refusal(command, caller).await.is_some() {
+            return Cooldown::Ready;
+        }
+        self.cooldowns
+            .check(command.name(), caller.id, caller.channel_id)
+    }
+
+    // Ask a member to slow down; slash commands get the notice where only they can see it
+    async fn send_cooldown_notice(&self, ctx: &Context, inv: &Invocation<'_>, wait: Duration) {
+        let response = self.cooldown_notice(wait);
+        match inv {
+            Invocation::Slash(interaction) => {
+                let notice = CreateInteractionResponseMessage::new()
+                    .embed(response.to_embed(&self.config.embed.thumbnail_url))
+                    .ephemeral(true);
+                let notice = CreateInteractionResponse::Message(notice);
+                if let Err(why) = interaction.create_response(&ctx.http, notice).await {
+                    error!(error = ?why, "Error sending cooldown notice");
                 }
-                // Send the formatted message as an embed
-                send_embed_message(
-                    "!get-campaigns",
-                    &campaign_message_details,
-                    Colour::DARK_ORANGE,
-                    &ctx,
-                    &msg,
-                    None,
-                )
-                .await;
             }
-            Err(err) => {
-                // Print an error message if fetching campaigns fails
-                println!("Error getting featured campaigns: {:?}", err);
+            Invocation::Prefix(_) => {
+                let responder = self.responder(ctx, inv, None);
+                if let Err(why) = responder.send(&response).await {
+                    error!(error = ?why, "Error sending cooldown notice");
+                }
             }
         }
     }
 
-    async fn handle_get_pathways(&self, ctx: &Context, msg: &Message) {
-        // Show loading animation
-        let loading_msg = show_loading_animation(ctx, msg, "Loading...").await;
-
-        // Fetch featured pathways and handle the result
-        match get_pathways(&self.http_client, &self.base_url).await {
-            Ok(pathways) => {
-                // Initialize the pathways message details
-                let mut pathways_message_details = String::from("**Featured pathways:** \n\n");
-                // Append each pathway's details to the message
-                for pathway in pathways {
-                    pathways_message_details.push_str(&format!(
-                        "**Title:** {}\n**Modules:** {}\n**Skills:** {}\n\n",
-                        pathway.title, pathway.modules, pathway.skills
-                    ));
+    // Send a response, replacing the loading message or the deferred slash command response
+    pub async fn send_response(
+        &self,
+        responder: &dyn Responder,
+        guild_id: Option<GuildId>,
+        response: &Response,
+    ) {
+        // Private responses go to the caller's DMs, leaving a note where the command was run.
+        // Commands run in DMs are already private, so they are answered the usual way.
+        if response.direct && guild_id.is_some() {
+            let sent = responder.direct_message(response).await;
+            let notice = match &sent {
+                Ok(()) => Response::new(
+                    "📬 Sent you the details in a direct message.",
+                    self.config.embed.colors.success,
+                ),
+                Err(why) => {
+                    error!(error = ?why, "Error sending direct message");
+                    Response::new(
+                        "I couldn't send you a direct message. Allow direct messages from \
+                         server members in your privacy settings and try again.",
+                        self.config.embed.colors.warning,
+                    )
                 }
-                // Send the formatted message as an embed
-                send_embed_message(
-                    "!get-pathways",
-                    &pathways_message_details,
-                    Colour::DARK_TEAL,
-                    &ctx,
-                    &msg,
-                    None,
-                )
-                .await;
+            };
+            if let Err(why) = responder.edit(&notice).await {
+                error!(error = ?why, "Error sending message");
             }
-            Err(err) => {
-                // Print an error message if fetching pathways fails
-                println!("Error getting featured pathways: {:?}", err);
+            // Mark the command as answered, so it's clear which one the direct message is about
+            if sent.is_ok() {
+                if let Err(why) = responder.react('📬').await {
+                    error!(error = ?why, "Error reacting to command");
+                }
             }
+            return;
+        }
+
+        // Responses with more fields than fit on a page get buttons to turn the pages
+        let mut pages = response.pages();
+        let result = if pages.len() > 1 {
+            let paginator = Paginator::starting_at(pages, response.start_page);
+            responder
+                .paginate(paginator, self.config.embed.page_timeout)
+                .await
+        } else {
+            responder.edit(&pages.remove(0)).await
+        };
+        if let Err(why) = result {
+            error!(error = ?why, "Error sending message");
         }
     }
 
-    async fn handle_get_hackathons(&self, ctx: &Context, msg: &Message) {
-        // Show loading animation
-        let loading_msg = show_loading_animation(ctx, msg, "Loading...").await;
-
-        // Fetch upcoming hackathons and handle the result
-        match get_hackathons(&self.http_client, &self.base_url).await {
-            Ok(hackathons) => {
-                // Initialize the hackathons message details
-                let mut hackathons_message_details = String::from("**Upcoming hackathons:** \n\n");
-                // Append each hackathon's details to the message
-                for hackathon in hackathons {
-                    hackathons_message_details.push_str(&format!(
-                        "**Title:** {}\n**Price:** ${}\n**Participating:** {}\n**Location:** {}\n\n",
-                        hackathon.title, hackathon.price, hackathon.participating, hackathon.location
-                    ));
-                }
-                // Send the formatted message as an embed
-                send_embed_message(
-                    "!get-hackathons",
-                    &hackathons_message_details,
-                    Colour::DARK_RED,
-                    &ctx,
-                    &msg,
-                    None,
-                )
-                .await;
+    // Send responses back to where a command was invoked from
+    fn responder<'a>(
+        &'a self,
+        ctx: &'a Context,
+        inv: &'a Invocation<'a>,
+        loading_msg: Option<Message>,
+    ) -> DiscordResponder<'a> {
+        DiscordResponder::new(ctx, inv, &self.config.embed.thumbnail_url, loading_msg)
+    }
+
+    // Run a request behind the loading indicator for prefix commands; slash commands are already
+    // deferred, which shows Discord's own "thinking" indicator
+    async fn with_loading<F: Future>(
+        &self,
+        ctx: &Context,
+        inv: &Invocation<'_>,
+        caller: &Caller,
+        request: F,
+    ) -> (F::Output, Option<Message>) {
+        match inv {
+            Invocation::Prefix(msg) => {
+                let threshold = self.config.loading_threshold;
+                with_loading_indicator(ctx, msg, &caller.colors, threshold, request).await
             }
-            Err(err) => {
-                // Print an error message if fetching hackathons fails
-                println!("Error getting upcoming hackathons: {:?}", err);
+            Invocation::Slash(_) => (request.await, None),
+        }
+    }
+
+    // Resolve the StackUp account of the mentioned member, or of the caller when nobody is
+    // mentioned, or the response explaining how to link it if it is missing
+    pub async fn linked_account(
+        &self,
+        caller: &Caller,
+        target: Option<UserId>,
+    ) -> Result<AccountLink, Response> {
+        let discord_id = target.unwrap_or(caller.id);
+        match self.store.account_link(discord_id).await {
+            Ok(Some(link)) => Ok(link),
+            Ok(None) => {
+                let not_linked_message_details = if discord_id == caller.id {
+                    NOT_LINKED_MESSAGE.to_string()
+                } else {
+                    format!("<@{}> hasn't linked their StackUp account yet.", discord_id)
+                };
+                Err(Response::new(
+                    not_linked_message_details,
+                    self.config.embed.colors.warning,
+                ))
             }
+            // Let the user know if looking up the link fails
+            Err(err) => Err(self.error_response(
+                "Error getting account link",
+                "Your account link couldn't be looked up. Please try again later.",
+                &err,
+                caller,
+            )),
         }
     }
 
-    async fn handle_get_calendar(&self, ctx: &Context, msg: &Message) {
-        // Show loading animation
-        let loading_msg = show_loading_animation(ctx, msg, "Loading...").await;
-
-        // Define the calendar link and image URL
-        let calendar_link = "https://stackup.dev/calendar";
-        const CALENDAR_IMG_URL: &str = "https://i.imgur.com/hxxfDQ9.png";
-        // Define the calendar message
-        const CALENDAR_MESSAGE: &str = "
-            Check out the latest activities happening on the **StackUp platform**, the platform for developers, where you can learn, earn and create projects.
-        ";
-        // Format the calendar details into a message
-        let calendar_details =
-            format!("{} [Monthly Calendar]({})", CALENDAR_MESSAGE, calendar_link);
-        // Send the formatted message as an embed with an image
-        send_embed_message(
-            "!get-calendar",
-            &calendar_details,
-            Colour::DARK_BLUE,
-            &ctx,
-            &msg,
-            Some(CALENDAR_IMG_URL),
-        )
-        .await;
+    // Ask a member who uses a command too often to wait before using it again
+    pub fn cooldown_notice(&self, wait: Duration) -> Response {
+        let cooldown_message_details = format!(
+            "Slow down, try again in {}s.",
+            wait.as_secs_f64().ceil().max(1.0)
+        );
+        Response::new(cooldown_message_details, self.config.embed.colors.warning)
+    }
+
+    // Report a failed command to the user, logging the full error under the same correlation ID
+    pub fn error_response(
+        &self,
+        log_message: &str,
+        reason: &str,
+        err: &dyn fmt::Debug,
+        caller: &Caller,
+    ) -> Response {
+        // Log the full error server-side, tagged with the invocation's ID
+        error!(
+            correlation_id = caller.correlation_id,
+            error = ?err,
+            "{}",
+            log_message
+        );
+
+        // A red embed with the short reason and the correlation ID to quote when reporting it
+        Response::new(reason, self.config.embed.colors.error)
+            .title("Something went wrong")
+            .footer(format!("Correlation ID: {}", caller.correlation_id))
+    }
+
+    // Report a failed StackUp request. While StackUp is down the user gets a friendly heads-up
+    // instead of an error report, since there is nothing for them to report.
+    pub fn api_error_response(
+        &self,
+        log_message: &str,
+        err: &StackUpApiError,
+        caller: &Caller,
+    ) -> Response {
+        match err {
+            StackUpApiError::Unavailable { .. } => {
+                let unavailable_message_details =
+                    format!("**StackUp is taking a break** ☕\n\n{}", err.reason());
+                Response::new(
+                    unavailable_message_details,
+                    self.config.embed.colors.warning,
+                )
+            }
+            _ => self.error_response(log_message, &err.reason(), err, caller),
+        }
+    }
+
+    // Tell the user what was wrong with a command's arguments and how to use it
+    pub fn usage_hint(&self, command: &dyn Command, err: &ArgError, prefix: &str) -> Response {
+        let usage_message_details = format!(
+            "{}\n\n**Usage:** `{}`\nSee `{}help {}` for more details.",
+            err,
+            command_signature(command, prefix),
+            prefix,
+            command.name()
+        );
+        Response::new(usage_message_details, self.config.embed.colors.warning)
     }
 }
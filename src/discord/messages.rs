@@ -1,5 +1,6 @@
 // Messages
-pub const HELP_MESSAGE: &str = "
+// The `!help` message is built from the registered commands, between this header and footer
+pub const HELP_HEADER: &str = "
 **🌟 GM Stackies! 🌟**
 
 I'm your **go-to stack-buddy**! Want to get information from the StackUp platform? Here are my commands:
@@ -8,15 +9,10 @@ I'm your **go-to stack-buddy**! Want to get information from the StackUp platfor
 
 **📋 Commands:**
 
-• **`!help`** - *Get a list of all commands.*
-• **`!info`** - *Learn more about what I can do.*
-• **`!get-username`** - *Retrieve your StackUp username.*
-• **`!get-balance`** - *Check your StackUp balance.*
-• **`!get-profile`** - *Check your StackUp profile.*
-• **`!get-campaigns`** - *View Featured campaigns.* 
-• **`!get-pathways`** - *View various resources for various tracks.* 
-• **`!get-hackathons`** - *See upcoming hackathons.*
-• **`!get-calendar`** - *Access the StackUp calendar.*
+";
+
+pub const HELP_FOOTER: &str = "
+Every command is also available as a slash command, e.g. **`/balance`** or **`/link`**.
 
 ────────────────────────
 
@@ -43,3 +39,13 @@ right here for you.
 
 — **Stack-Buddy** 🤖
 ";
+
+pub const NOT_LINKED_MESSAGE: &str = "
+**You haven't linked your StackUp account yet!** 🔗
+
+To use this command, link your Discord account to your StackUp user ID first:
+
+• **`/link <stackup-id>`** - *e.g. `/link stackup-id:42`*
+
+You can remove the link at any time with **`/unlink`**.
+";
@@ -0,0 +1,114 @@
+use crate::discord::response::Response;
+use serenity::builder::{CreateActionRow, CreateButton};
+use serenity::model::application::ButtonStyle;
+
+// The buttons below a paginated response
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PageButton {
+    First,
+    Previous,
+    Next,
+    Last,
+}
+
+impl PageButton {
+    const ALL: [PageButton; 4] = [
+        PageButton::First,
+        PageButton::Previous,
+        PageButton::Next,
+        PageButton::Last,
+    ];
+
+    // The ID Discord sends back when the button is pressed
+    pub fn custom_id(self) -> &'static str {
+        match self {
+            PageButton::First => "page-first",
+            PageButton::Previous => "page-previous",
+            PageButton::Next => "page-next",
+            PageButton::Last => "page-last",
+        }
+    }
+
+    // Find the button with the given ID
+    pub fn from_custom_id(custom_id: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|button| button.custom_id() == custom_id)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            PageButton::First => "⏮ First",
+            PageButton::Previous => "◀ Previous",
+            PageButton::Next => "Next ▶",
+            PageButton::Last => "Last ⏭",
+        }
+    }
+}
+
+// Keeps track of which page of a response is shown and which buttons lead somewhere
+pub struct Paginator {
+    pages: Vec<Response>,
+    current: usize,
+}
+
+impl Paginator {
+    // Start on the first of the given pages, which must not be empty
+    pub fn new(pages: Vec<Response>) -> Self {
+        assert!(!pages.is_empty(), "a paginator needs at least one page");
+        Self { pages, current: 0 }
+    }
+
+    // Start on the page at `index`, or on the last page when there are fewer pages than that
+    pub fn starting_at(pages: Vec<Response>, index: usize) -> Self {
+        let mut paginator = Self::new(pages);
+        paginator.current = index.min(paginator.pages.len() - 1);
+        paginator
+    }
+
+    // The page being shown
+    pub fn page(&self) -> &Response {
+        &self.pages[self.current]
+    }
+
+    // The index of the page being shown, starting at zero
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    // Move to the page a button leads to, returning whether the page changed
+    pub fn press(&mut self, button: PageButton) -> bool {
+        let last = self.pages.len() - 1;
+        let target = match button {
+            PageButton::First => 0,
+            PageButton::Previous => self.current.saturating_sub(1),
+            PageButton::Next => (self.current + 1).min(last),
+            PageButton::Last => last,
+        };
+        let changed = target != self.current;
+        self.current = target;
+        changed
+    }
+
+    // Whether a button leads to another page from the current one
+    pub fn is_enabled(&self, button: PageButton) -> bool {
+        match button {
+            PageButton::First | PageButton::Previous => self.current > 0,
+            PageButton::Next | PageButton::Last => self.current + 1 < self.pages.len(),
+        }
+    }
+
+    // Build the row of page buttons, disabling the ones that lead nowhere
+    pub fn buttons(&self) -> Vec<CreateActionRow> {
+        let buttons = PageButton::ALL
+            .into_iter()
+            .map(|button| {
+                CreateButton::new(button.custom_id())
+                    .label(button.label())
+                    .style(ButtonStyle::Secondary)
+                    .disabled(!self.is_enabled(button))
+            })
+            .collect();
+        vec![CreateActionRow::Buttons(buttons)]
+    }
+}
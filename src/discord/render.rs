@@ -0,0 +1,219 @@
+use crate::config::EmbedColors;
+use crate::discord::response::{Field, Response};
+use crate::models::{Campaigns, GetUserResponse, Hackathons, Pathways, UserBalance, UserProgress};
+use chrono::{DateTime, Utc};
+use serenity::model::colour::Colour;
+
+// The StackUp pages the list titles link to
+const CAMPAIGNS_URL: &str = "https://stackup.dev/campaigns";
+const PATHWAYS_URL: &str = "https://stackup.dev/pathways";
+const HACKATHONS_URL: &str = "https://stackup.dev/hackathons";
+
+// Render a user's details, with the username as the title
+pub fn user_details(user: &GetUserResponse, colors: &EmbedColors) -> Response {
+    Response::new("", colors.username)
+        .title(&user.username)
+        .fields([
+            Field::new("Nationality", &user.nationality, true),
+            Field::new("Career Level", user.career_level.to_string(), true),
+            Field::new("Role", user.role.to_string(), true),
+            Field::new("Tech Stacks", user.tech_stack.join(", "), false),
+        ])
+        .footer(format!("StackUp user ID {}", user.id))
+}
+
+// Render a balance breakdown, where `owner` is "Your" or the owner's mention
+pub fn balance(owner: &str, balance: &UserBalance, colors: &EmbedColors) -> Response {
+    let withdrawal_methods = if balance.withdrawal_methods.is_empty() {
+        "*None set up*".to_string()
+    } else {
+        balance.withdrawal_methods.join(", ")
+    };
+    let mut fields = vec![
+        Field::new("Current Balance", balance.current_balance.to_string(), true),
+        Field::new(
+            "Lifetime Earnings",
+            balance.total_earnings.to_string(),
+            true,
+        ),
+        Field::new("Withdrawn", balance.total_withdrawn.to_string(), true),
+        Field::new("Withdrawal Methods", withdrawal_methods, false),
+    ];
+    if let Some(updated_at) = balance.updated_at {
+        fields.push(Field::new("Last Updated", relative_time(updated_at), false));
+    }
+
+    Response::new(format!("{} StackUp balance", owner), colors.balance)
+        .fields(fields)
+        .footer(format!("StackUp user ID {}", balance.user_id))
+}
+
+// Render a user's progress, hiding the earnings unless `show_earnings`, where `owner` is "Your"
+// or the owner's mention
+pub fn progress(
+    owner: &str,
+    progress: &UserProgress,
+    show_earnings: bool,
+    colors: &EmbedColors,
+) -> Response {
+    let total_earnings = if show_earnings {
+        progress.total_quest_earnings.to_string()
+    } else {
+        "*Private*".to_string()
+    };
+    Response::new(format!("{} StackUp profile", owner), colors.profile)
+        .fields([
+            Field::new("Submissions", progress.submissions.to_string(), true),
+            Field::new("Submitted", progress.submitted.to_string(), true),
+            Field::new("Rewarded", progress.rewarded.to_string(), true),
+            Field::new("Total Earnings", total_earnings, true),
+        ])
+        .footer(format!("StackUp user ID {}", progress.user_id))
+}
+
+// Render the featured campaigns, one field per campaign
+pub fn campaigns(campaigns: &[Campaigns], colors: &EmbedColors) -> Response {
+    list(
+        "Featured campaigns",
+        CAMPAIGNS_URL,
+        "campaigns",
+        colors.campaigns,
+        campaigns.iter().map(campaign_field),
+    )
+}
+
+// Render the featured pathways, one field per pathway
+pub fn pathways(pathways: &[Pathways], colors: &EmbedColors) -> Response {
+    list(
+        "Featured pathways",
+        PATHWAYS_URL,
+        "pathways",
+        colors.pathways,
+        pathways.iter().map(pathway_field),
+    )
+}
+
+// Render the upcoming hackathons, one field per hackathon
+pub fn hackathons(hackathons: &[Hackathons], colors: &EmbedColors) -> Response {
+    list(
+        "Upcoming hackathons",
+        HACKATHONS_URL,
+        "hackathons",
+        colors.hackathons,
+        hackathons.iter().map(hackathon_field),
+    )
+}
+
+// Announce a campaign that has just been featured
+pub fn new_campaign(campaign: &Campaigns, colors: &EmbedColors) -> Response {
+    announcement(
+        "New campaign!",
+        campaign.url.as_deref().unwrap_or(CAMPAIGNS_URL),
+        colors.campaigns,
+        campaign_field(campaign),
+    )
+}
+
+// Announce a pathway that has just been featured
+pub fn new_pathway(pathway: &Pathways, colors: &EmbedColors) -> Response {
+    announcement(
+        "New pathway!",
+        pathway.url.as_deref().unwrap_or(PATHWAYS_URL),
+        colors.pathways,
+        pathway_field(pathway),
+    )
+}
+
+// Announce a hackathon that has just been listed
+pub fn new_hackathon(hackathon: &Hackathons, colors: &EmbedColors) -> Response {
+    announcement(
+        "New hackathon!",
+        hackathon.url.as_deref().unwrap_or(HACKATHONS_URL),
+        colors.hackathons,
+        hackathon_field(hackathon),
+    )
+}
+
+fn campaign_field(campaign: &Campaigns) -> Field {
+    let mut details = format!(
+        "{}\n**Quest Count:** {}",
+        campaign.sub_title, campaign.quest_count
+    );
+    if let Some(end_date) = campaign.end_date {
+        details.push_str(&format!("\n**Ends:** {}", relative_time(end_date)));
+    }
+    Field::new(
+        &campaign.title,
+        with_link(details, campaign.url.as_deref()),
+        false,
+    )
+}
+
+fn pathway_field(pathway: &Pathways) -> Field {
+    let details = format!(
+        "**Modules:** {}\n**Skills:** {}",
+        pathway.modules, pathway.skills
+    );
+    Field::new(
+        &pathway.title,
+        with_link(details, pathway.url.as_deref()),
+        false,
+    )
+}
+
+fn hackathon_field(hackathon: &Hackathons) -> Field {
+    let mut details = format!(
+        "**Price:** {}\n**Participating:** {}\n**Location:** {}",
+        hackathon.price, hackathon.participating, hackathon.location
+    );
+    if let Some(start_date) = hackathon.start_date {
+        details.push_str(&format!("\n**Starts:** {}", relative_time(start_date)));
+    }
+    Field::new(
+        &hackathon.title,
+        with_link(details, hackathon.url.as_deref()),
+        false,
+    )
+}
+
+// Render a single new item under a linked title
+fn announcement(title: &str, url: &str, color: Colour, field: Field) -> Response {
+    Response::new("", color)
+        .title(title)
+        .url(url)
+        .fields([field])
+}
+
+// Render a list under a linked title, with the number of items in the footer or a note when
+// there are none. The page size is filled in by the command, since it comes from the config.
+fn list(
+    title: &str,
+    url: &str,
+    items: &str,
+    color: Colour,
+    fields: impl Iterator<Item = Field>,
+) -> Response {
+    let response = Response::new("", color)
+        .title(title)
+        .url(url)
+        .fields(fields);
+    match response.fields.len() {
+        0 => Response::new(format!("There are no {} right now.", items), color)
+            .title(title)
+            .url(url),
+        count => response.footer(format!("{} in total", count)),
+    }
+}
+
+// Add a link to an item's page below its details, if it has one
+fn with_link(details: String, url: Option<&str>) -> String {
+    match url {
+        Some(url) => format!("{}\n[View on StackUp]({})", details, url),
+        None => details,
+    }
+}
+
+// Format a time for Discord to show in each reader's timezone, e.g. "in 3 days"
+fn relative_time(time: DateTime<Utc>) -> String {
+    format!("<t:{}:R>", time.timestamp())
+}
@@ -0,0 +1,239 @@
+use crate::discord::discord::Invocation;
+use crate::discord::paginator::{PageButton, Paginator};
+use crate::discord::response::Response;
+use async_trait::async_trait;
+use serenity::builder::{
+    CreateActionRow, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage,
+    CreateMessage, EditInteractionResponse, EditMessage,
+};
+use serenity::collector::ComponentInteractionCollector;
+use serenity::futures::StreamExt;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+use std::pin::pin;
+use std::time::Duration;
+use tokio::sync::Mutex as AsyncMutex;
+use tokio::time;
+use tracing::error;
+
+// Where a command's responses go. The bot sends them to Discord; tests can record them instead.
+#[async_trait]
+pub trait Responder: Send + Sync {
+    // Send the response as a new message
+    async fn send(&self, response: &Response) -> serenity::Result<()>;
+
+    // Replace the bot's pending message (the loading message or the deferred slash command
+    // response) with the response, sending it as a new message if there is none
+    async fn edit(&self, response: &Response) -> serenity::Result<()>;
+
+    // Send the response as a reply to the invoking message
+    async fn reply(&self, response: &Response) -> serenity::Result<()>;
+
+    // React to the invoking message with an emoji
+    async fn react(&self, emoji: char) -> serenity::Result<()>;
+
+    // Send the response to the invoking user's DMs
+    async fn direct_message(&self, response: &Response) -> serenity::Result<()>;
+
+    // Show the first page in place of the pending message, with buttons that let the invoking
+    // user turn the pages until nobody has pressed one for `timeout`. Returns once the first
+    // page is shown, the pages keep turning in the background.
+    async fn paginate(&self, paginator: Paginator, timeout: Duration) -> serenity::Result<()>;
+}
+
+// Sends responses back to where the command was invoked from on Discord
+pub struct DiscordResponder<'a> {
+    ctx: &'a Context,
+    inv: &'a Invocation<'a>,
+    thumbnail_url: &'a str,
+    // The loading message shown while the command ran, taken by the first `edit`
+    loading_msg: AsyncMutex<Option<Message>>,
+}
+
+impl<'a> DiscordResponder<'a> {
+    pub fn new(
+        ctx: &'a Context,
+        inv: &'a Invocation<'a>,
+        thumbnail_url: &'a str,
+        loading_msg: Option<Message>,
+    ) -> Self {
+        Self {
+            ctx,
+            inv,
+            thumbnail_url,
+            loading_msg: AsyncMutex::new(loading_msg),
+        }
+    }
+
+    // Put an embed and its buttons in place of the pending message, returning the message
+    async fn show(
+        &self,
+        embed: CreateEmbed,
+        components: Vec<CreateActionRow>,
+    ) -> serenity::Result<Message> {
+        let loading_msg = self.loading_msg.lock().await.take();
+        match (self.inv, loading_msg) {
+            (Invocation::Prefix(_), Some(mut loading_msg)) => {
+                let edit_message = EditMessage::new().embed(embed).components(components);
+                loading_msg.edit(&self.ctx.http, edit_message).await?;
+                Ok(loading_msg)
+            }
+            (Invocation::Prefix(msg), None) => {
+                let message = CreateMessage::default().embed(embed).components(components);
+                msg.channel_id.send_message(&self.ctx.http, message).await
+            }
+            // Slash commands are deferred, so their reply is the deferred response
+            (Invocation::Slash(command), _) => {
+                let response = EditInteractionResponse::new()
+                    .embed(embed)
+                    .components(components);
+                command.edit_response(&self.ctx.http, response).await
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Responder for DiscordResponder<'_> {
+    async fn send(&self, response: &Response) -> serenity::Result<()> {
+        let embed = response.to_embed(self.thumbnail_url);
+        match self.inv {
+            Invocation::Prefix(msg) => {
+                let message = CreateMessage::default().embed(embed);
+                msg.channel_id
+                    .send_message(&self.ctx.http, message)
+                    .await
+                    .map(|_| ())
+            }
+            Invocation::Slash(command) => {
+                let response = EditInteractionResponse::new().embed(embed);
+                command
+                    .edit_response(&self.ctx.http, response)
+                    .await
+                    .map(|_| ())
+            }
+        }
+    }
+
+    async fn edit(&self, response: &Response) -> serenity::Result<()> {
+        self.show(response.to_embed(self.thumbnail_url), Vec::new())
+            .await
+            .map(|_| ())
+    }
+
+    async fn reply(&self, response: &Response) -> serenity::Result<()> {
+        match self.inv {
+            Invocation::Prefix(msg) => {
+                let message = CreateMessage::default()
+                    .embed(response.to_embed(self.thumbnail_url))
+                    .reference_message(*msg);
+                msg.channel_id
+                    .send_message(&self.ctx.http, message)
+                    .await
+                    .map(|_| ())
+            }
+            Invocation::Slash(_) => self.send(response).await,
+        }
+    }
+
+    async fn react(&self, emoji: char) -> serenity::Result<()> {
+        match self.inv {
+            Invocation::Prefix(msg) => msg.react(&self.ctx.http, emoji).await.map(|_| ()),
+            // Interactions can't be reacted to, so react to the bot's reply instead
+            Invocation::Slash(command) => {
+                let reply = command.get_response(&self.ctx.http).await?;
+                reply.react(&self.ctx.http, emoji).await.map(|_| ())
+            }
+        }
+    }
+
+    async fn direct_message(&self, response: &Response) -> serenity::Result<()> {
+        let message = CreateMessage::default().embed(response.to_embed(self.thumbnail_url));
+        self.inv
+            .author()
+            .direct_message(&self.ctx.http, message)
+            .await
+            .map(|_| ())
+    }
+
+    async fn paginate(&self, paginator: Paginator, timeout: Duration) -> serenity::Result<()> {
+        let message = self
+            .show(
+                paginator.page().to_embed(self.thumbnail_url),
+                paginator.buttons(),
+            )
+            .await?;
+
+        // Turn the pages in the background, so the command is answered once the first page is up
+        let turner = PageTurner {
+            ctx: self.ctx.clone(),
+            author_id: self.inv.author().id,
+            slash_command: match self.inv {
+                Invocation::Prefix(_) => None,
+                Invocation::Slash(command) => Some((*command).clone()),
+            },
+            thumbnail_url: self.thumbnail_url.to_string(),
+            paginator,
+        };
+        tokio::spawn(turner.run(message, timeout));
+        Ok(())
+    }
+}
+
+// Turns a paginated message's pages as its buttons are pressed, outliving the command
+struct PageTurner {
+    ctx: Context,
+    author_id: UserId,
+    // The slash command the message answers, whose response is edited to remove the buttons
+    slash_command: Option<CommandInteraction>,
+    thumbnail_url: String,
+    paginator: Paginator,
+}
+
+impl PageTurner {
+    async fn run(mut self, mut message: Message, timeout: Duration) {
+        // Collect the message's button presses on a single stream, so none are missed while a
+        // page is being turned, giving up once none have come in for a while
+        let mut presses = pin!(ComponentInteractionCollector::new(&self.ctx)
+            .message_id(message.id)
+            .stream());
+        while let Ok(Some(press)) = time::timeout(timeout, presses.next()).await {
+            // Only the member who ran the command can turn its pages
+            let response = if press.user.id != self.author_id {
+                let notice = CreateInteractionResponseMessage::new()
+                    .content(format!("Only <@{}> can turn these pages.", self.author_id))
+                    .ephemeral(true);
+                CreateInteractionResponse::Message(notice)
+            } else {
+                if let Some(button) = PageButton::from_custom_id(&press.data.custom_id) {
+                    self.paginator.press(button);
+                }
+                let page = CreateInteractionResponseMessage::new()
+                    .embed(self.paginator.page().to_embed(&self.thumbnail_url))
+                    .components(self.paginator.buttons());
+                CreateInteractionResponse::UpdateMessage(page)
+            };
+            if let Err(why) = press.create_response(&self.ctx.http, response).await {
+                error!(error = ?why, "Error turning page");
+            }
+        }
+
+        // Remove the buttons once they have expired
+        let result = match &self.slash_command {
+            None => {
+                let edit_message = EditMessage::new().components(Vec::new());
+                message.edit(&self.ctx.http, edit_message).await
+            }
+            Some(command) => {
+                let response = EditInteractionResponse::new().components(Vec::new());
+                command
+                    .edit_response(&self.ctx.http, response)
+                    .await
+                    .map(|_| ())
+            }
+        };
+        if let Err(why) = result {
+            error!(error = ?why, "Error removing page buttons");
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use serenity::builder::{CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter};
+use serenity::model::colour::Colour;
+use serenity::model::Timestamp;
+
+// The name shown at the top of every embed
+const AUTHOR: &str = "Stack-Buddy";
+
+// Discord's limits on the number of characters in each part of an embed, and in all of them
+const TITLE_LIMIT: usize = 256;
+const DESCRIPTION_LIMIT: usize = 4096;
+const FIELD_NAME_LIMIT: usize = 256;
+const FIELD_VALUE_LIMIT: usize = 1024;
+const FIELD_COUNT_LIMIT: usize = 25;
+const FOOTER_LIMIT: usize = 2048;
+const TOTAL_LIMIT: usize = 6000;
+
+// A command's reply, built by the command and rendered into an embed when it is sent
+#[derive(Clone, Debug, PartialEq)]
+pub struct Response {
+    pub title: Option<String>,
+    // Where the title links to
+    pub url: Option<String>,
+    pub description: String,
+    pub color: Colour,
+    pub fields: Vec<Field>,
+    pub image_url: Option<String>,
+    pub footer: Option<String>,
+    // How many fields to show per page; responses with more fields are split into pages
+    pub page_size: Option<usize>,
+    // The page shown first when the response is split into pages, counting from zero
+    pub start_page: usize,
+    // Whether to send the response to the caller's DMs instead of where the command was run
+    pub direct: bool,
+}
+
+// A titled block of text in an embed
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub value: String,
+    // Whether the field can sit next to other inline fields
+    pub inline: bool,
+}
+
+impl Field {
+    pub fn new(name: impl Into<String>, value: impl Into<String>, inline: bool) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            inline,
+        }
+    }
+}
+
+impl Response {
+    // Create a response with a description and an accent color
+    pub fn new(description: impl Into<String>, color: Colour) -> Self {
+        Self {
+            title: None,
+            url: None,
+            description: description.into(),
+            color,
+            fields: Vec::new(),
+            image_url: None,
+            footer: None,
+            page_size: None,
+            start_page: 0,
+            direct: false,
+        }
+    }
+
+    // Show a title above the description
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    // Link the title to a page
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    // Show fields below the description
+    pub fn fields(mut self, fields: impl IntoIterator<Item = Field>) -> Self {
+        self.fields.extend(fields);
+        self
+    }
+
+    // Show a large image below the description
+    pub fn image(mut self, image_url: impl Into<String>) -> Self {
+        self.image_url = Some(image_url.into());
+        self
+    }
+
+    // Show a small line of text at the bottom
+    pub fn footer(mut self, footer: impl Into<String>) -> Self {
+        self.footer = Some(footer.into());
+        self
+    }
+
+    // Send the response to the caller's DMs, or to where the command was run when `direct` is
+    // false
+    pub fn direct(mut self, direct: bool) -> Self {
+        self.direct = direct;
+        self
+    }
+
+    // Split the fields into pages of `page_size` once there are more than fit on one
+    pub fn paginate(mut self, page_size: usize) -> Self {
+        self.page_size = Some(page_size.max(1));
+        self
+    }
+
+    // Show the given page first, counting from zero; lists that are shorter than that start on
+    // their last page
+    pub fn start_at(mut self, page: usize) -> Self {
+        self.start_page = page;
+        self
+    }
+
+    // Split the response into the pages to show, each with its share of the fields and the
+    // page number in the footer. Responses that fit on one page are returned as they are.
+    pub fn pages(&self) -> Vec<Response> {
+        let page_size = match self.page_size {
+            Some(page_size) if self.fields.len() > page_size => page_size,
+            _ => return vec![self.clone()],
+        };
+
+        let chunks: Vec<&[Field]> = self.fields.chunks(page_size).collect();
+        let count = chunks.len();
+        chunks
+            .into_iter()
+            .enumerate()
+            .map(|(index, fields)| {
+                let page_number = format!("Page {} of {}", index + 1, count);
+                Response {
+                    fields: fields.to_vec(),
+                    footer: Some(match &self.footer {
+                        Some(footer) => format!("{} • {}", footer, page_number),
+                        None => page_number,
+                    }),
+                    page_size: None,
+                    start_page: 0,
+                    ..self.clone()
+                }
+            })
+            .collect()
+    }
+
+    // Cut the response down to fit Discord's embed limits, shortening text that is too long
+    // and then the description and fields if the embed as a whole is still too big
+    pub fn truncated(&self) -> Response {
+        let mut response = Response {
+            title: self
+                .title
+                .as_deref()
+                .map(|title| truncate(title, TITLE_LIMIT)),
+            description: truncate(&self.description, DESCRIPTION_LIMIT),
+            fields: self
+                .fields
+                .iter()
+                .take(FIELD_COUNT_LIMIT)
+                .map(|field| Field {
+                    name: truncate(&field.name, FIELD_NAME_LIMIT),
+                    value: truncate(&field.value, FIELD_VALUE_LIMIT),
+                    inline: field.inline,
+                })
+                .collect(),
+            footer: self
+                .footer
+                .as_deref()
+                .map(|footer| truncate(footer, FOOTER_LIMIT)),
+            ..self.clone()
+        };
+
+        // Shorten the description first, then drop fields from the end if that wasn't enough
+        let overflow = response.len().saturating_sub(TOTAL_LIMIT);
+        if overflow > 0 {
+            let length = response
+                .description
+                .chars()
+                .count()
+                .saturating_sub(overflow);
+            response.description = truncate(&response.description, length);
+        }
+        while response.len() > TOTAL_LIMIT && response.fields.pop().is_some() {}
+
+        response
+    }
+
+    // How many characters of the response count towards the embed's total limit
+    fn len(&self) -> usize {
+        let fields: usize = self
+            .fields
+            .iter()
+            .map(|field| field.name.chars().count() + field.value.chars().count())
+            .sum();
+        AUTHOR.len()
+            + self
+                .title
+                .as_deref()
+                .map_or(0, |title| title.chars().count())
+            + self.description.chars().count()
+            + fields
+            + self
+                .footer
+                .as_deref()
+                .map_or(0, |footer| footer.chars().count())
+    }
+
+    // Render the response as a Stack-Buddy embed with the given thumbnail, truncated to fit
+    pub fn to_embed(&self, thumbnail_url: &str) -> CreateEmbed {
+        let response = self.truncated();
+        let mut embed = CreateEmbed::default()
+            .author(CreateEmbedAuthor::new(AUTHOR))
+            .thumbnail(thumbnail_url)
+            .timestamp(Timestamp::now())
+            .color(response.color)
+            .fields(
+                response
+                    .fields
+                    .iter()
+                    .map(|field| (&field.name, &field.value, field.inline)),
+            );
+
+        // Discord rejects empty descriptions, so responses made of fields leave it out
+        if !response.description.is_empty() {
+            embed = embed.description(&response.description);
+        }
+        if let Some(title) = &response.title {
+            embed = embed.title(title);
+        }
+        if let Some(url) = &response.url {
+            embed = embed.url(url);
+        }
+        if let Some(image_url) = &response.image_url {
+            embed = embed.image(image_url);
+        }
+        if let Some(footer) = &response.footer {
+            embed = embed.footer(CreateEmbedFooter::new(footer));
+        }
+
+        embed
+    }
+}
+
+// Shorten text to at most `limit` characters, ending it with an ellipsis when it is cut
+fn truncate(text: &str, limit: usize) -> String {
+    if text.chars().count() <= limit {
+        return text.to_string();
+    }
+    if limit == 0 {
+        return String::new();
+    }
+
+    let mut truncated: String = text.chars().take(limit.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
@@ -0,0 +1,16 @@
+use serenity::builder::CreateCommand;
+use serenity::model::application::Command;
+use serenity::model::id::GuildId;
+use serenity::prelude::*;
+
+// Register the slash commands with a single guild, or globally when no guild is given
+pub async fn register_slash_commands(
+    ctx: &Context,
+    guild_id: Option<GuildId>,
+    commands: Vec<CreateCommand>,
+) -> serenity::Result<Vec<Command>> {
+    match guild_id {
+        Some(guild_id) => guild_id.set_commands(&ctx.http, commands).await,
+        None => Command::set_global_commands(&ctx.http, commands).await,
+    }
+}
@@ -0,0 +1,263 @@
+use crate::config::parse_color;
+use crate::store::{Store, StoreError};
+use serenity::model::colour::Colour;
+use serenity::model::id::{ChannelId, GuildId};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+// The command that changes these settings; it can't be turned off or restricted to some
+// channels, so a server can't lock itself out of it
+pub const CONFIG_COMMAND: &str = "config";
+
+// The settings each server can change with `!config`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Setting {
+    Prefix,
+    AnnouncementChannel,
+    AllowedChannels,
+    DisabledCommands,
+    AccentColor,
+}
+
+impl Setting {
+    pub const ALL: [Setting; 5] = [
+        Setting::Prefix,
+        Setting::AnnouncementChannel,
+        Setting::AllowedChannels,
+        Setting::DisabledCommands,
+        Setting::AccentColor,
+    ];
+
+    // The name the setting is stored and typed as
+    pub fn key(self) -> &'static str {
+        match self {
+            Setting::Prefix => "prefix",
+            Setting::AnnouncementChannel => "announcement-channel",
+            Setting::AllowedChannels => "allowed-channels",
+            Setting::DisabledCommands => "disabled-commands",
+            Setting::AccentColor => "accent-color",
+        }
+    }
+
+    // What a valid value looks like, for error messages
+    pub fn expected(self) -> &'static str {
+        match self {
+            Setting::Prefix => "a prefix without spaces, like `?`",
+            Setting::AnnouncementChannel => "a channel, like `#announcements`",
+            Setting::AllowedChannels => "one or more channels, like `#bots #general`",
+            Setting::DisabledCommands => "one or more command names, like `balance profile`",
+            Setting::AccentColor => "a color, like `#1F8B4C`",
+        }
+    }
+
+    // Turn a value typed by a member into the form it is stored in, or None if it is malformed.
+    // Lists are typed separated by spaces or commas and stored separated by commas.
+    pub fn normalize(self, value: &str) -> Option<String> {
+        let items: Vec<&str> = value
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|item| !item.is_empty())
+            .collect();
+        match self {
+            Setting::Prefix => Some(value.trim().to_string())
+                .filter(|prefix| !prefix.is_empty() && !prefix.contains(char::is_whitespace)),
+            Setting::AnnouncementChannel => match items[..] {
+                [channel] => parse_channel(channel).map(|id| id.to_string()),
+                _ => None,
+            },
+            Setting::AllowedChannels => {
+                let channels: Option<Vec<String>> = items
+                    .iter()
+                    .map(|channel| parse_channel(channel).map(|id| id.to_string()))
+                    .collect();
+                channels
+                    .filter(|channels| !channels.is_empty())
+                    .map(|channels| channels.join(","))
+            }
+            Setting::DisabledCommands => Some(
+                items
+                    .iter()
+                    .map(|name| name.to_lowercase())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            )
+            .filter(|names| !names.is_empty()),
+            Setting::AccentColor => {
+                parse_color(value.trim()).map(|color| format!("#{}", color.hex()))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Setting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+impl FromStr for Setting {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim().to_lowercase().replace('_', "-");
+        Setting::ALL
+            .into_iter()
+            .find(|setting| setting.key() == value)
+            .ok_or(())
+    }
+}
+
+// A server's settings; anything left unset falls back to the bot's config
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GuildSettings {
+    pub prefix: Option<String>,
+    // Where new campaigns and hackathons are posted, alongside the configured channels
+    pub announcement_channel: Option<ChannelId>,
+    // The channels commands can be used in; every channel is allowed when empty
+    pub allowed_channels: Vec<ChannelId>,
+    // The names of the commands turned off in the server
+    pub disabled_commands: Vec<String>,
+    // Used for every kind of embed except the success, warning and error ones
+    pub accent_color: Option<Colour>,
+}
+
+impl GuildSettings {
+    // Read the settings from their stored values, skipping any that no longer parse
+    pub fn from_stored(values: &HashMap<String, String>) -> Self {
+        let mut settings = GuildSettings::default();
+        for (key, value) in values {
+            let Ok(setting) = key.parse::<Setting>() else {
+                continue;
+            };
+            let list = || value.split(',').map(str::to_string);
+            match setting {
+                Setting::Prefix => settings.prefix = Some(value.clone()),
+                Setting::AnnouncementChannel => {
+                    settings.announcement_channel = parse_channel(value)
+                }
+                Setting::AllowedChannels => {
+                    settings.allowed_channels = list()
+                        .filter_map(|channel| parse_channel(&channel))
+                        .collect()
+                }
+                Setting::DisabledCommands => settings.disabled_commands = list().collect(),
+                Setting::AccentColor => settings.accent_color = parse_color(value),
+            }
+        }
+        settings
+    }
+
+    // The prefix commands start with in the server
+    pub fn prefix<'a>(&'a self, default: &'a str) -> &'a str {
+        self.prefix.as_deref().unwrap_or(default)
+    }
+
+    // Whether a command has been turned off in the server
+    pub fn is_disabled(&self, command: &str) -> bool {
+        command != CONFIG_COMMAND && self.disabled_commands.iter().any(|name| name == command)
+    }
+
+    // Whether a command can be used in a channel of the server
+    pub fn is_allowed_in(&self, command: &str, channel_id: ChannelId) -> bool {
+        command == CONFIG_COMMAND
+            || self.allowed_channels.is_empty()
+            || self.allowed_channels.contains(&channel_id)
+    }
+
+    // Show a setting's current value, as `!config get` lists it
+    pub fn describe(&self, setting: Setting) -> Option<String> {
+        let channels = |channels: &[ChannelId]| {
+            channels
+                .iter()
+                .map(|channel| format!("<#{}>", channel))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let names = |names: &[String]| {
+            names
+                .iter()
+                .map(|name| format!("`{}`", name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match setting {
+            Setting::Prefix => self.prefix.as_ref().map(|prefix| format!("`{}`", prefix)),
+            Setting::AnnouncementChannel => self
+                .announcement_channel
+                .map(|channel| format!("<#{}>", channel)),
+            Setting::AllowedChannels => {
+                Some(channels(&self.allowed_channels)).filter(|value| !value.is_empty())
+            }
+            Setting::DisabledCommands => {
+                Some(names(&self.disabled_commands)).filter(|value| !value.is_empty())
+            }
+            Setting::AccentColor => self.accent_color.map(|color| format!("`#{}`", color.hex())),
+        }
+    }
+}
+
+// Each server's settings, read from the store once and kept in memory until they change
+pub struct GuildSettingsCache {
+    store: Arc<dyn Store>,
+    settings: RwLock<HashMap<GuildId, Arc<GuildSettings>>>,
+}
+
+impl GuildSettingsCache {
+    pub fn new(store: Arc<dyn Store>) -> Self {
+        Self {
+            store,
+            settings: RwLock::new(HashMap::new()),
+        }
+    }
+
+    // Get a server's settings
+    pub async fn get(&self, guild_id: GuildId) -> Result<Arc<GuildSettings>, StoreError> {
+        if let Some(settings) = self.settings.read().await.get(&guild_id) {
+            return Ok(settings.clone());
+        }
+
+        let stored = self.store.guild_settings(guild_id).await?;
+        let settings = Arc::new(GuildSettings::from_stored(&stored));
+        self.settings
+            .write()
+            .await
+            .insert(guild_id, settings.clone());
+        Ok(settings)
+    }
+
+    // Change a server's setting to a value already normalized with `Setting::normalize`
+    pub async fn set(
+        &self,
+        guild_id: GuildId,
+        setting: Setting,
+        value: &str,
+    ) -> Result<(), StoreError> {
+        self.store
+            .set_guild_setting(guild_id, setting.key(), value)
+            .await?;
+        self.settings.write().await.remove(&guild_id);
+        Ok(())
+    }
+
+    // Put a server's setting back to its default, returning false if it wasn't changed
+    pub async fn reset(&self, guild_id: GuildId, setting: Setting) -> Result<bool, StoreError> {
+        let removed = self
+            .store
+            .remove_guild_setting(guild_id, setting.key())
+            .await?;
+        self.settings.write().await.remove(&guild_id);
+        Ok(removed)
+    }
+}
+
+// Parse a channel mention (`<#123>`) or a raw channel ID
+pub fn parse_channel(value: &str) -> Option<ChannelId> {
+    let id = value
+        .strip_prefix("<#")
+        .and_then(|value| value.strip_suffix('>'))
+        .unwrap_or(value);
+
+    id.parse().ok().filter(|id| *id != 0).map(ChannelId::new)
+}
@@ -0,0 +1,197 @@
+use crate::api::StackUpApiError;
+use crate::config::ApiConfig;
+use rand::Rng;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Client as ReqwestClient, Response, StatusCode};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+use tracing::warn;
+
+// An HTTP client for the StackUp API that retries failed requests and stops calling the API
+// for a while once it looks to be down. Cloning it shares the same connection pool and breaker.
+#[derive(Clone)]
+pub struct HttpClient {
+    client: ReqwestClient,
+    max_retries: u32,
+    retry_base_delay: Duration,
+    retry_max_delay: Duration,
+    breaker: Arc<CircuitBreaker>,
+}
+
+impl HttpClient {
+    // Wrap a client with the retries and circuit breaker from the config
+    pub fn new(client: ReqwestClient, config: &ApiConfig) -> Self {
+        Self {
+            client,
+            max_retries: config.max_retries,
+            retry_base_delay: config.retry_base_delay,
+            retry_max_delay: config.retry_max_delay,
+            breaker: Arc::new(CircuitBreaker::new(
+                config.breaker_threshold,
+                config.breaker_cooldown,
+            )),
+        }
+    }
+
+    // Send a GET request, retrying connection errors, 5xx and 429 responses with backoff.
+    // Returns the response once it has a successful status, or the error of the last attempt.
+    pub async fn get(&self, url: &str) -> Result<Response, StackUpApiError> {
+        // Fail fast while StackUp is known to be down
+        self.breaker.check()?;
+
+        let mut attempt = 0;
+        loop {
+            let result = match self.client.get(url).send().await {
+                Ok(response) => check_status(response),
+                Err(err) => Err(StackUpApiError::Network(err)),
+            };
+
+            let err = match result {
+                Ok(response) => {
+                    self.breaker.record_success();
+                    return Ok(response);
+                }
+                Err(err) => err,
+            };
+
+            match self.retry_delay(&err, attempt) {
+                Some(delay) => {
+                    warn!(
+                        url,
+                        attempt = attempt + 1,
+                        delay_ms = delay.as_millis() as u64,
+                        error = %err,
+                        "Retrying failed StackUp request"
+                    );
+                    attempt += 1;
+                    sleep(delay).await;
+                }
+                None => {
+                    // Only failures that mean StackUp itself is struggling count towards the
+                    // breaker. Being rate limited says nothing either way, so it leaves it be.
+                    if is_outage(&err) {
+                        self.breaker.record_failure();
+                    } else if !matches!(err, StackUpApiError::RateLimited { .. }) {
+                        self.breaker.record_success();
+                    }
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    // Work out how long to wait before retrying a failed attempt, or None to give up
+    fn retry_delay(&self, err: &StackUpApiError, attempt: u32) -> Option<Duration> {
+        if attempt >= self.max_retries {
+            return None;
+        }
+
+        match err {
+            // Wait as long as StackUp asks, unless that is longer than we'd ever wait anyway
+            StackUpApiError::RateLimited {
+                retry_after: Some(retry_after),
+            } => Some(*retry_after).filter(|delay| *delay <= self.retry_max_delay),
+            StackUpApiError::RateLimited { retry_after: None } => Some(self.backoff(attempt)),
+            err if is_outage(err) => Some(self.backoff(attempt)),
+            _ => None,
+        }
+    }
+
+    // Exponential backoff with jitter, so retries from many commands don't arrive all at once
+    fn backoff(&self, attempt: u32) -> Duration {
+        let delay = self
+            .retry_base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.retry_max_delay);
+        delay.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+}
+
+// Map unsuccessful status codes to the matching error
+fn check_status(response: Response) -> Result<Response, StackUpApiError> {
+    let status = response.status();
+    match status {
+        StatusCode::NOT_FOUND => Err(StackUpApiError::NotFound),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(StackUpApiError::Unauthorized),
+        StatusCode::TOO_MANY_REQUESTS => {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse().ok())
+                .map(Duration::from_secs);
+            Err(StackUpApiError::RateLimited { retry_after })
+        }
+        _ if !status.is_success() => Err(StackUpApiError::Http(status)),
+        _ => Ok(response),
+    }
+}
+
+// Whether an error means StackUp is down or unreachable, rather than rejecting the request
+fn is_outage(err: &StackUpApiError) -> bool {
+    match err {
+        StackUpApiError::Network(_) => true,
+        StackUpApiError::Http(status) => status.is_server_error(),
+        _ => false,
+    }
+}
+
+// Tracks failed requests and short-circuits calls for a cooldown once too many fail in a row
+struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+struct BreakerState {
+    consecutive_failures: u32,
+    // Calls fail straight away until this time while the breaker is open
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            state: Mutex::new(BreakerState {
+                consecutive_failures: 0,
+                open_until: None,
+            }),
+        }
+    }
+
+    // Refuse the call while the breaker is open. Once the cooldown is over calls go through
+    // again, and the next failure opens it straight back up until a call succeeds.
+    fn check(&self) -> Result<(), StackUpApiError> {
+        let state = self.state.lock().unwrap();
+        match state.open_until {
+            Some(open_until) if open_until > Instant::now() => Err(StackUpApiError::Unavailable {
+                retry_in: open_until - Instant::now(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn record_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures = 0;
+        state.open_until = None;
+    }
+
+    fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures += 1;
+        if state.consecutive_failures >= self.threshold {
+            if state.open_until.is_none() {
+                warn!(
+                    "StackUp failed {} requests in a row, pausing calls for {}s",
+                    state.consecutive_failures,
+                    self.cooldown.as_secs()
+                );
+            }
+            state.open_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+}
@@ -0,0 +1,27 @@
+// Import the API, cache, config, cooldown, HTTP, logging, models, store, discord and utility
+// modules
+pub mod api;
+pub mod cache;
+pub mod config;
+pub mod cooldowns;
+pub mod guild_settings;
+pub mod http;
+pub mod links;
+pub mod logging;
+pub mod models;
+pub mod store;
+pub mod subscriptions;
+pub mod util;
+pub mod discord {
+    pub mod announcer;
+    pub mod arguments;
+    pub mod commands;
+    #[allow(clippy::module_inception)]
+    pub mod discord;
+    pub mod messages;
+    pub mod paginator;
+    pub mod render;
+    pub mod responder;
+    pub mod response;
+    pub mod slash;
+}
@@ -0,0 +1,7 @@
+// A Discord user's link to their StackUp account
+#[derive(Clone, Copy, Debug)]
+pub struct AccountLink {
+    pub stackup_id: i32,
+    // Whether the balance is hidden from other members; balances are private until opted out
+    pub private: bool,
+}
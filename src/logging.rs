@@ -0,0 +1,36 @@
+use crate::config::{LogFormat, LogLevel};
+use serenity::model::id::{ChannelId, GuildId, UserId};
+use tracing::{info_span, Span};
+use tracing_subscriber::EnvFilter;
+
+// Send log lines to stdout in the chosen format. The bot logs at `level` and other crates
+// only log warnings and errors, unless `RUST_LOG` asks for something else.
+pub fn init(level: LogLevel, format: LogFormat) {
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(format!("warn,stack_buddy={}", level.as_str())));
+    let logger = tracing_subscriber::fmt().with_env_filter(filter);
+    match format {
+        LogFormat::Text => logger.init(),
+        LogFormat::Json => logger.json().with_current_span(true).init(),
+    }
+}
+
+// The span a command runs in, carrying who ran it, where, and how long it took to answer.
+// Everything logged while handling the command, StackUp calls included, is nested under it.
+pub fn command_span(
+    command: &str,
+    user_id: UserId,
+    guild_id: Option<GuildId>,
+    channel_id: ChannelId,
+    correlation_id: u64,
+) -> Span {
+    info_span!(
+        "command",
+        command,
+        user = user_id.get(),
+        guild = guild_id.map(GuildId::get),
+        channel = channel_id.get(),
+        correlation_id,
+        latency_ms = tracing::field::Empty,
+    )
+}
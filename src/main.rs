@@ -1,46 +1,74 @@
-use crate::discord::discord::Handler;
 use dotenv::dotenv;
-use reqwest::Client as ReqwestClient;
 use serenity::prelude::*;
 use serenity::Client;
-use std::env;
-
-// Import the API, models, discord and utility modules
-pub mod api;
-pub mod models;
-pub mod util;
-pub mod discord {
-    pub mod commands;
-    pub mod discord;
-    pub mod messages;
-}
+use stack_buddy::api::{StackUpApi, StackUpClient};
+use stack_buddy::cache::ApiCache;
+use stack_buddy::config::{Config, LogFormat, LogLevel};
+use stack_buddy::cooldowns::Cooldowns;
+use stack_buddy::discord::announcer::Announcer;
+use stack_buddy::discord::commands::CommandRegistry;
+use stack_buddy::discord::discord::Handler;
+use stack_buddy::guild_settings::GuildSettingsCache;
+use stack_buddy::logging;
+use stack_buddy::store::{prune_command_usage, SqliteStore, Store};
+use std::sync::Arc;
+use tracing::error;
 
 #[tokio::main]
 async fn main() {
     // Load environment variables from a .env file
     dotenv().ok();
 
-    // Retrieve the Discord token from the environment variables
-    let token = env::var("DISCORD_TOKEN").expect("Expected a token in the environment");
-    // Define the base URL for the API
-    let base_url = "https://superna.ytechno.com.ng/api";
-    // Create a new HTTP client
-    let http_client = ReqwestClient::new();
+    // Load the bot's settings from the config file and environment, listing every problem
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(why) => {
+            // The log settings are part of the config, so fall back to the defaults to report it
+            logging::init(LogLevel::Info, LogFormat::Text);
+            error!("Err loading config: {}", why);
+            std::process::exit(1);
+        }
+    };
+    // Log at the configured level and in the configured format from here on
+    logging::init(config.log_level, config.log_format);
+    // Create the StackUp API client, with the configured timeouts, retries and circuit breaker
+    let client: Arc<dyn StackUpApi> =
+        Arc::new(StackUpClient::new(&config.api).expect("Err creating StackUp client"));
+    // Answer repeated requests from the cache instead of calling the API every time
+    let cache = Arc::new(ApiCache::new(client.clone(), &config.cache));
+    // Open the database the bot keeps its state in, creating or upgrading it as needed
+    let store: Arc<dyn Store> =
+        Arc::new(SqliteStore::open(&config.database_file).expect("Err opening database"));
+    let announcer = Announcer::new(client, store.clone(), config.clone());
+    // Keep the command usage statistics from growing forever
+    tokio::spawn(prune_command_usage(store.clone(), config.usage_retention));
 
-    // Define the intents for the Discord bot (listening to guild messages and message content)
-    let intents = GatewayIntents::GUILD_MESSAGES | GatewayIntents::MESSAGE_CONTENT;
+    // Define the intents for the Discord bot (slash commands need none beyond guilds)
+    let mut intents = GatewayIntents::GUILDS;
+    if config.prefix_commands {
+        // Listening to guild messages and message content for the prefix commands
+        intents |= GatewayIntents::GUILD_MESSAGES | GatewayIntents::MESSAGE_CONTENT;
+    }
 
     // Create a new Discord client with the specified token and intents
-    let mut client = Client::builder(&token, intents)
+    let mut client = Client::builder(config.discord_token.expose(), intents)
         .event_handler(Handler {
-            http_client,
-            base_url: base_url.to_string(),
+            api: cache.clone(),
+            cache,
+            guild_settings: GuildSettingsCache::new(store.clone()),
+            cooldowns: Cooldowns::new(config.cooldowns.clone()),
+            store,
+            commands: CommandRegistry::default(),
+            config,
         })
         .await
         .expect("Err creating client");
 
+    // Check StackUp for new campaigns, hackathons and pathways in the background
+    tokio::spawn(announcer.run(client.http.clone()));
+
     // Start the client and handle any errors that occur
     if let Err(why) = client.start().await {
-        println!("Client error: {:?}", why);
+        error!(error = ?why, "Client error");
     }
 }
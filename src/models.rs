@@ -1,64 +1,377 @@
-use serde::Deserialize;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+// The models tolerate StackUp changing its responses: unknown fields are ignored and missing
+// ones fall back to their defaults, so only malformed values fail to decode.
 
 // Struct Format of API Response for getting user details
-#[derive(Deserialize, Debug)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
 pub struct GetUserResponse {
     pub id: i32,
     pub username: String,
     pub nationality: String,
-    pub career_level: String,
-    pub role: String,
-    pub tech_stack: String,
+    pub career_level: CareerLevel,
+    pub role: Role,
+    // Sent as a comma-separated string, e.g. "Rust, TypeScript"
+    #[serde(deserialize_with = "list")]
+    pub tech_stack: Vec<String>,
 }
 
 // Struct Format of API Response for getting user balance
-#[derive(Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
 pub struct UserBalance {
-    id: i32,
-    user_id: String,
-    total_earnings: i32,
-    total_withdrawn: i32,
-    withdrawal_methods: String,
-    pub current_balance: i32,
-    created_at: String,
-    updated_at: String,
+    pub id: i32,
+    #[serde(deserialize_with = "string_or_number")]
+    pub user_id: String,
+    pub total_earnings: Money,
+    pub total_withdrawn: Money,
+    // Sent as a comma-separated string, e.g. "paypal, bank"
+    #[serde(deserialize_with = "list")]
+    pub withdrawal_methods: Vec<String>,
+    pub current_balance: Money,
+    #[serde(with = "timestamp")]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(with = "timestamp")]
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 // Struct Format of API Response for getting user profile
-#[derive(Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
 pub struct UserProgress {
-    id: i32,
-    user_id: String,
+    pub id: i32,
+    #[serde(deserialize_with = "string_or_number")]
+    pub user_id: String,
     pub submissions: i32,
     pub submitted: i32,
     pub rewarded: i32,
-    pub total_quest_earings: i32,
+    // StackUp misspells the field's name
+    #[serde(rename = "total_quest_earings")]
+    pub total_quest_earnings: Money,
 }
 
 // Struct Format of API Response for getting featured campaigns
-#[derive(Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
 pub struct Campaigns {
-    id: i32,
+    pub id: i32,
     pub title: String,
     pub sub_title: String,
     pub quest_count: i32,
+    pub url: Option<String>,
+    #[serde(with = "timestamp")]
+    pub start_date: Option<DateTime<Utc>>,
+    #[serde(with = "timestamp")]
+    pub end_date: Option<DateTime<Utc>>,
 }
 
 // Struct Format of API Response for getting pathways
-#[derive(Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
 pub struct Pathways {
-    id: i32,
+    pub id: i32,
     pub title: String,
     pub modules: i32,
     pub skills: i32,
+    pub url: Option<String>,
 }
 
 // Struct Format of API Response for getting hackathons
-#[derive(Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
 pub struct Hackathons {
-    id: i32,
+    pub id: i32,
     pub title: String,
-    pub price: i32,
+    // The prize pool
+    pub price: Money,
     pub participating: i32,
-    pub location: i32, 
+    // Where it takes place, e.g. "Online"
+    #[serde(deserialize_with = "string_or_number")]
+    pub location: String,
+    pub url: Option<String>,
+    #[serde(with = "timestamp")]
+    pub start_date: Option<DateTime<Utc>>,
+    #[serde(with = "timestamp")]
+    pub end_date: Option<DateTime<Utc>>,
+}
+
+// An amount of money, kept in cents so amounts add up exactly.
+// StackUp sends amounts as plain numbers (or numeric strings) in US dollars.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Money {
+    pub cents: i64,
+    pub currency: Currency,
+}
+
+// The currencies StackUp pays out in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Currency {
+    #[default]
+    Usd,
+}
+
+impl Currency {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Currency::Usd => "$",
+        }
+    }
+}
+
+impl Money {
+    // An amount in US dollars, given in cents
+    pub fn usd(cents: i64) -> Self {
+        Self {
+            cents,
+            currency: Currency::Usd,
+        }
+    }
+}
+
+// Whole amounts are shown without cents, e.g. `$250` but `$12.50`
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.cents < 0 { "-" } else { "" };
+        let (units, cents) = (self.cents.abs() / 100, self.cents.abs() % 100);
+        write!(f, "{}{}{}", sign, self.currency.symbol(), units)?;
+        if cents != 0 {
+            write!(f, ".{:02}", cents)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Money {
+    type Err = String;
+
+    // Parse a decimal amount in dollars, e.g. `12`, `12.5` or `-0.99`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid amount of money: {:?}", value);
+        let value = value.trim();
+        let (negative, digits) = match value.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, value),
+        };
+        let (units, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        if units.is_empty() && fraction.is_empty()
+            || !units.chars().all(|c| c.is_ascii_digit())
+            || !fraction.chars().all(|c| c.is_ascii_digit())
+            || fraction.len() > 2
+        {
+            return Err(invalid());
+        }
+
+        let units: i64 = if units.is_empty() {
+            0
+        } else {
+            units.parse().map_err(|_| invalid())?
+        };
+        let cents: i64 = format!("{:0<2}", fraction).parse().map_err(|_| invalid())?;
+        let total = units
+            .checked_mul(100)
+            .and_then(|total| total.checked_add(cents))
+            .ok_or_else(invalid)?;
+        Ok(Money::usd(if negative { -total } else { total }))
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Amount {
+            Whole(i64),
+            Decimal(f64),
+            Text(String),
+        }
+
+        // A null amount is treated like a missing one
+        match Option::<Amount>::deserialize(deserializer)? {
+            None => Ok(Money::default()),
+            Some(Amount::Whole(units)) => units
+                .checked_mul(100)
+                .map(Money::usd)
+                .ok_or_else(|| de::Error::custom("amount of money is too large")),
+            Some(Amount::Decimal(amount)) => Ok(Money::usd((amount * 100.0).round() as i64)),
+            Some(Amount::Text(text)) => text.parse().map_err(de::Error::custom),
+        }
+    }
+}
+
+// Amounts are written as exact decimal strings, e.g. "12.50"
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let sign = if self.cents < 0 { "-" } else { "" };
+        let (units, cents) = (self.cents.abs() / 100, self.cents.abs() % 100);
+        serializer.serialize_str(&format!("{}{}.{:02}", sign, units, cents))
+    }
+}
+
+// How far along their career a user is; values StackUp adds later are kept as they are
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum CareerLevel {
+    Student,
+    Beginner,
+    Intermediate,
+    Advanced,
+    Expert,
+    #[default]
+    Unknown,
+    Other(String),
+}
+
+// What a user does; values StackUp adds later are kept as they are
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Role {
+    Developer,
+    Designer,
+    DataScientist,
+    ProductManager,
+    Student,
+    #[default]
+    Unknown,
+    Other(String),
+}
+
+// Map each enum to and from the text StackUp uses, case-insensitively
+macro_rules! text_enum {
+    ($name:ident { $($variant:ident => $text:literal),* $(,)? }) => {
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $text,)*
+                    $name::Unknown => "Unknown",
+                    $name::Other(text) => text,
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(text: String) -> Self {
+                match text.trim() {
+                    $(value if value.eq_ignore_ascii_case($text) => $name::$variant,)*
+                    "" => $name::Unknown,
+                    value => $name::Other(value.to_string()),
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                Ok(Option::<String>::deserialize(deserializer)?
+                    .unwrap_or_default()
+                    .into())
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                match self {
+                    $name::Unknown => serializer.serialize_none(),
+                    _ => serializer.serialize_str(self.as_str()),
+                }
+            }
+        }
+    };
+}
+
+text_enum!(CareerLevel {
+    Student => "Student",
+    Beginner => "Beginner",
+    Intermediate => "Intermediate",
+    Advanced => "Advanced",
+    Expert => "Expert",
+});
+
+text_enum!(Role {
+    Developer => "Developer",
+    Designer => "Designer",
+    DataScientist => "Data Scientist",
+    ProductManager => "Product Manager",
+    Student => "Student",
+});
+
+// Read a list sent either as a comma-separated string or as an array of strings
+fn list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum List {
+        Items(Vec<String>),
+        Text(String),
+    }
+
+    let items = match Option::<List>::deserialize(deserializer)? {
+        Some(List::Items(items)) => items,
+        Some(List::Text(text)) => text.split(',').map(String::from).collect(),
+        None => Vec::new(),
+    };
+    Ok(items
+        .into_iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect())
+}
+
+// Read a value StackUp sends either as a string or as a number
+fn string_or_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        Text(String),
+        Number(serde_json::Number),
+    }
+
+    Ok(match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::Text(text)) => text,
+        Some(Value::Number(number)) => number.to_string(),
+        None => String::new(),
+    })
+}
+
+// Timestamps, sent as RFC 3339 (`2024-06-01T12:30:00Z`) or as `2024-06-01 12:30:00` in UTC.
+// Missing or empty timestamps are None.
+mod timestamp {
+    use super::*;
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<DateTime<Utc>>, D::Error> {
+        let Some(text) = Option::<String>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+
+        DateTime::parse_from_rfc3339(text)
+            .map(|timestamp| timestamp.with_timezone(&Utc))
+            .or_else(|_| {
+                NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S")
+                    .map(|timestamp| timestamp.and_utc())
+            })
+            .map(Some)
+            .map_err(|_| de::Error::custom(format!("invalid timestamp: {:?}", text)))
+    }
+
+    pub fn serialize<S: Serializer>(
+        timestamp: &Option<DateTime<Utc>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match timestamp {
+            Some(timestamp) => serializer.serialize_str(&timestamp.to_rfc3339()),
+            None => serializer.serialize_none(),
+        }
+    }
 }
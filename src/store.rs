@@ -0,0 +1,506 @@
+use crate::links::AccountLink;
+use crate::subscriptions::Topic;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serenity::model::id::{GuildId, UserId};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{error, info};
+
+// The schema, one step per release that changed it. The database remembers how many steps it
+// has had in `PRAGMA user_version`, so only the new ones run on startup. Never edit a step once
+// it has shipped, add another one instead.
+const MIGRATIONS: &[&str] = &[
+    // 1: account links, subscriptions, guild settings, seen feed items and command usage
+    "CREATE TABLE account_links (
+        discord_id INTEGER PRIMARY KEY,
+        stackup_id INTEGER NOT NULL,
+        private INTEGER NOT NULL DEFAULT 1
+    );
+    CREATE TABLE subscriptions (
+        discord_id INTEGER NOT NULL,
+        topic TEXT NOT NULL,
+        PRIMARY KEY (discord_id, topic)
+    );
+    CREATE TABLE guild_settings (
+        guild_id INTEGER NOT NULL,
+        key TEXT NOT NULL,
+        value TEXT NOT NULL,
+        PRIMARY KEY (guild_id, key)
+    );
+    CREATE TABLE feeds (
+        topic TEXT PRIMARY KEY,
+        polled_at INTEGER NOT NULL
+    );
+    CREATE TABLE seen_items (
+        topic TEXT NOT NULL,
+        item_id INTEGER NOT NULL,
+        PRIMARY KEY (topic, item_id)
+    );
+    CREATE TABLE command_usage (
+        id INTEGER PRIMARY KEY,
+        command TEXT NOT NULL,
+        discord_id INTEGER NOT NULL,
+        guild_id INTEGER,
+        used_at INTEGER NOT NULL
+    );
+    CREATE INDEX command_usage_command ON command_usage (command);",
+];
+
+// Errors that can occur when reading or writing the bot's state
+#[derive(Debug)]
+pub enum StoreError {
+    // The database couldn't be opened, migrated or queried
+    Database(rusqlite::Error),
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Database(err) => write!(f, "database error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StoreError::Database(err) => Some(err),
+        }
+    }
+}
+
+impl From<rusqlite::Error> for StoreError {
+    fn from(err: rusqlite::Error) -> Self {
+        StoreError::Database(err)
+    }
+}
+
+// How often a command has been used, for the usage statistics
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandUsage {
+    pub command: String,
+    pub uses: u64,
+}
+
+// Everything the bot remembers between restarts. Features only talk to this trait, so tests
+// can run them against an in-memory database.
+#[async_trait]
+pub trait Store: Send + Sync {
+    // Get the StackUp account linked to a Discord user, if any
+    async fn account_link(&self, discord_id: UserId) -> Result<Option<AccountLink>, StoreError>;
+
+    // Link a Discord user to a StackUp user ID, replacing any previous link but keeping their
+    // privacy setting
+    async fn link(&self, discord_id: UserId, stackup_id: i32) -> Result<(), StoreError>;
+
+    // Remove the link for a Discord user, returning the StackUp user ID it pointed to
+    async fn unlink(&self, discord_id: UserId) -> Result<Option<i32>, StoreError>;
+
+    // Choose whether a Discord user's balance is hidden from other members, returning false if
+    // they haven't linked an account
+    async fn set_private(&self, discord_id: UserId, private: bool) -> Result<bool, StoreError>;
+
+    // Subscribe a Discord user to a topic, returning false if they already were
+    async fn subscribe(&self, discord_id: UserId, topic: Topic) -> Result<bool, StoreError>;
+
+    // Unsubscribe a Discord user from a topic, returning false if they weren't subscribed
+    async fn unsubscribe(&self, discord_id: UserId, topic: Topic) -> Result<bool, StoreError>;
+
+    // Get the topics a Discord user is subscribed to
+    async fn topics(&self, discord_id: UserId) -> Result<Vec<Topic>, StoreError>;
+
+    // Get the Discord users subscribed to a topic
+    async fn subscribers(&self, topic: Topic) -> Result<Vec<UserId>, StoreError>;
+
+    // Get every setting a guild has changed from the defaults, by key
+    async fn guild_settings(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<HashMap<String, String>, StoreError>;
+
+    // Change one of a guild's settings
+    async fn set_guild_setting(
+        &self,
+        guild_id: GuildId,
+        key: &str,
+        value: &str,
+    ) -> Result<(), StoreError>;
+
+    // Get every guild that has changed a setting, with the value they changed it to
+    async fn guilds_with_setting(&self, key: &str) -> Result<Vec<(GuildId, String)>, StoreError>;
+
+    // Put one of a guild's settings back to its default, returning false if it wasn't changed
+    async fn remove_guild_setting(&self, guild_id: GuildId, key: &str) -> Result<bool, StoreError>;
+
+    // Get the IDs of the items last seen in a feed, or None if it has never been polled
+    async fn seen_items(&self, topic: Topic) -> Result<Option<HashSet<i32>>, StoreError>;
+
+    // Replace the IDs of the items last seen in a feed
+    async fn save_seen_items(
+        &self,
+        topic: Topic,
+        item_ids: &HashSet<i32>,
+    ) -> Result<(), StoreError>;
+
+    // Record that a command was run
+    async fn record_command(
+        &self,
+        command: &str,
+        discord_id: UserId,
+        guild_id: Option<GuildId>,
+    ) -> Result<(), StoreError>;
+
+    // Count how often each command has been run, most used first
+    async fn command_usage(&self) -> Result<Vec<CommandUsage>, StoreError>;
+
+    // Forget the command uses recorded before a time, returning how many were removed
+    async fn prune_command_usage(&self, before: DateTime<Utc>) -> Result<usize, StoreError>;
+}
+
+// The store, kept in an SQLite database. SQLite blocks while it reads and writes the file, so
+// queries run on tokio's blocking threads, one at a time.
+pub struct SqliteStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteStore {
+    // Open (or create) the database file and bring its schema up to date
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        Self::new(Connection::open(path)?)
+    }
+
+    // Open a fresh database that only lives in memory, for tests
+    pub fn in_memory() -> Result<Self, StoreError> {
+        Self::new(Connection::open_in_memory()?)
+    }
+
+    fn new(mut conn: Connection) -> Result<Self, StoreError> {
+        migrate(&mut conn)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    // Run a query with the connection on a blocking thread
+    async fn with<T: Send + 'static>(
+        &self,
+        query: impl FnOnce(&mut Connection) -> rusqlite::Result<T> + Send + 'static,
+    ) -> Result<T, StoreError> {
+        let conn = self.conn.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let mut conn = conn.lock().unwrap_or_else(|err| err.into_inner());
+            query(&mut conn)
+        })
+        .await;
+        match result {
+            Ok(result) => Ok(result?),
+            // Pass on a panic in the query as if it had happened here
+            Err(err) => std::panic::resume_unwind(err.into_panic()),
+        }
+    }
+}
+
+// Apply the migrations the database hasn't had yet, each in its own transaction
+fn migrate(conn: &mut Connection) -> rusqlite::Result<()> {
+    let applied: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(applied) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", version + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+// Discord IDs fit in 63 bits, so they are stored as SQLite's signed integers
+fn sql_id(id: u64) -> i64 {
+    id as i64
+}
+
+#[async_trait]
+impl Store for SqliteStore {
+    async fn account_link(&self, discord_id: UserId) -> Result<Option<AccountLink>, StoreError> {
+        self.with(move |conn| {
+            conn.query_row(
+                "SELECT stackup_id, private FROM account_links WHERE discord_id = ?1",
+                [sql_id(discord_id.get())],
+                |row| {
+                    Ok(AccountLink {
+                        stackup_id: row.get(0)?,
+                        private: row.get(1)?,
+                    })
+                },
+            )
+            .optional()
+        })
+        .await
+    }
+
+    async fn link(&self, discord_id: UserId, stackup_id: i32) -> Result<(), StoreError> {
+        self.with(move |conn| {
+            conn.execute(
+                "INSERT INTO account_links (discord_id, stackup_id) VALUES (?1, ?2)
+                 ON CONFLICT (discord_id) DO UPDATE SET stackup_id = excluded.stackup_id",
+                params![sql_id(discord_id.get()), stackup_id],
+            )
+            .map(|_| ())
+        })
+        .await
+    }
+
+    async fn unlink(&self, discord_id: UserId) -> Result<Option<i32>, StoreError> {
+        self.with(move |conn| {
+            conn.query_row(
+                "DELETE FROM account_links WHERE discord_id = ?1 RETURNING stackup_id",
+                [sql_id(discord_id.get())],
+                |row| row.get(0),
+            )
+            .optional()
+        })
+        .await
+    }
+
+    async fn set_private(&self, discord_id: UserId, private: bool) -> Result<bool, StoreError> {
+        self.with(move |conn| {
+            conn.execute(
+                "UPDATE account_links SET private = ?2 WHERE discord_id = ?1",
+                params![sql_id(discord_id.get()), private],
+            )
+            .map(|updated| updated > 0)
+        })
+        .await
+    }
+
+    async fn subscribe(&self, discord_id: UserId, topic: Topic) -> Result<bool, StoreError> {
+        self.with(move |conn| {
+            conn.execute(
+                "INSERT OR IGNORE INTO subscriptions (discord_id, topic) VALUES (?1, ?2)",
+                params![sql_id(discord_id.get()), topic.as_str()],
+            )
+            .map(|inserted| inserted > 0)
+        })
+        .await
+    }
+
+    async fn unsubscribe(&self, discord_id: UserId, topic: Topic) -> Result<bool, StoreError> {
+        self.with(move |conn| {
+            conn.execute(
+                "DELETE FROM subscriptions WHERE discord_id = ?1 AND topic = ?2",
+                params![sql_id(discord_id.get()), topic.as_str()],
+            )
+            .map(|deleted| deleted > 0)
+        })
+        .await
+    }
+
+    async fn topics(&self, discord_id: UserId) -> Result<Vec<Topic>, StoreError> {
+        let topics: Vec<String> = self
+            .with(move |conn| {
+                conn.prepare(
+                    "SELECT topic FROM subscriptions WHERE discord_id = ?1 ORDER BY topic",
+                )?
+                .query_map([sql_id(discord_id.get())], |row| row.get(0))?
+                .collect()
+            })
+            .await?;
+        // Topics this version doesn't know about are skipped
+        Ok(topics
+            .iter()
+            .filter_map(|topic| topic.parse().ok())
+            .collect())
+    }
+
+    async fn subscribers(&self, topic: Topic) -> Result<Vec<UserId>, StoreError> {
+        let subscribers: Vec<i64> = self
+            .with(move |conn| {
+                conn.prepare(
+                    "SELECT discord_id FROM subscriptions WHERE topic = ?1 ORDER BY discord_id",
+                )?
+                .query_map([topic.as_str()], |row| row.get(0))?
+                .collect()
+            })
+            .await?;
+        Ok(subscribers
+            .into_iter()
+            .map(|discord_id| UserId::new(discord_id as u64))
+            .collect())
+    }
+
+    async fn guild_settings(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<HashMap<String, String>, StoreError> {
+        self.with(move |conn| {
+            conn.prepare("SELECT key, value FROM guild_settings WHERE guild_id = ?1")?
+                .query_map([sql_id(guild_id.get())], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })?
+                .collect()
+        })
+        .await
+    }
+
+    async fn set_guild_setting(
+        &self,
+        guild_id: GuildId,
+        key: &str,
+        value: &str,
+    ) -> Result<(), StoreError> {
+        let (key, value) = (key.to_string(), value.to_string());
+        self.with(move |conn| {
+            conn.execute(
+                "INSERT INTO guild_settings (guild_id, key, value) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (guild_id, key) DO UPDATE SET value = excluded.value",
+                params![sql_id(guild_id.get()), key, value],
+            )
+            .map(|_| ())
+        })
+        .await
+    }
+
+    async fn guilds_with_setting(&self, key: &str) -> Result<Vec<(GuildId, String)>, StoreError> {
+        let key = key.to_string();
+        let guilds: Vec<(i64, String)> = self
+            .with(move |conn| {
+                conn.prepare(
+                    "SELECT guild_id, value FROM guild_settings WHERE key = ?1 ORDER BY guild_id",
+                )?
+                .query_map([&key], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect()
+            })
+            .await?;
+        Ok(guilds
+            .into_iter()
+            .map(|(guild_id, value)| (GuildId::new(guild_id as u64), value))
+            .collect())
+    }
+
+    async fn remove_guild_setting(&self, guild_id: GuildId, key: &str) -> Result<bool, StoreError> {
+        let key = key.to_string();
+        self.with(move |conn| {
+            conn.execute(
+                "DELETE FROM guild_settings WHERE guild_id = ?1 AND key = ?2",
+                params![sql_id(guild_id.get()), key],
+            )
+            .map(|deleted| deleted > 0)
+        })
+        .await
+    }
+
+    async fn seen_items(&self, topic: Topic) -> Result<Option<HashSet<i32>>, StoreError> {
+        self.with(move |conn| {
+            let polled = conn
+                .query_row(
+                    "SELECT 1 FROM feeds WHERE topic = ?1",
+                    [topic.as_str()],
+                    |_| Ok(()),
+                )
+                .optional()?;
+            if polled.is_none() {
+                return Ok(None);
+            }
+
+            conn.prepare("SELECT item_id FROM seen_items WHERE topic = ?1")?
+                .query_map([topic.as_str()], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()
+                .map(Some)
+        })
+        .await
+    }
+
+    async fn save_seen_items(
+        &self,
+        topic: Topic,
+        item_ids: &HashSet<i32>,
+    ) -> Result<(), StoreError> {
+        let item_ids = item_ids.clone();
+        self.with(move |conn| {
+            let tx = conn.transaction()?;
+            tx.execute(
+                "INSERT INTO feeds (topic, polled_at) VALUES (?1, ?2)
+                 ON CONFLICT (topic) DO UPDATE SET polled_at = excluded.polled_at",
+                params![topic.as_str(), Utc::now().timestamp()],
+            )?;
+            tx.execute("DELETE FROM seen_items WHERE topic = ?1", [topic.as_str()])?;
+            {
+                let mut insert =
+                    tx.prepare("INSERT INTO seen_items (topic, item_id) VALUES (?1, ?2)")?;
+                for item_id in &item_ids {
+                    insert.execute(params![topic.as_str(), item_id])?;
+                }
+            }
+            tx.commit()
+        })
+        .await
+    }
+
+    async fn record_command(
+        &self,
+        command: &str,
+        discord_id: UserId,
+        guild_id: Option<GuildId>,
+    ) -> Result<(), StoreError> {
+        let command = command.to_string();
+        self.with(move |conn| {
+            conn.execute(
+                "INSERT INTO command_usage (command, discord_id, guild_id, used_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    command,
+                    sql_id(discord_id.get()),
+                    guild_id.map(|guild_id| sql_id(guild_id.get())),
+                    Utc::now().timestamp(),
+                ],
+            )
+            .map(|_| ())
+        })
+        .await
+    }
+
+    async fn command_usage(&self) -> Result<Vec<CommandUsage>, StoreError> {
+        self.with(move |conn| {
+            conn.prepare(
+                "SELECT command, COUNT(*) AS uses FROM command_usage
+                 GROUP BY command ORDER BY uses DESC, command",
+            )?
+            .query_map([], |row| {
+                Ok(CommandUsage {
+                    command: row.get(0)?,
+                    uses: row.get(1)?,
+                })
+            })?
+            .collect()
+        })
+        .await
+    }
+
+    async fn prune_command_usage(&self, before: DateTime<Utc>) -> Result<usize, StoreError> {
+        self.with(move |conn| {
+            conn.execute(
+                "DELETE FROM command_usage WHERE used_at < ?1",
+                [before.timestamp()],
+            )
+        })
+        .await
+    }
+}
+
+// Once a day, forget the command uses older than `retention`, so the usage table doesn't grow
+// forever
+pub async fn prune_command_usage(store: Arc<dyn Store>, retention: Duration) {
+    let mut interval = tokio::time::interval(Duration::from_secs(24 * 60 * 60));
+    loop {
+        interval.tick().await;
+        let before = Utc::now() - retention;
+        match store.prune_command_usage(before).await {
+            Ok(0) => {}
+            Ok(removed) => info!("Removed {} command uses older than {}", removed, before),
+            Err(why) => error!(error = ?why, "Error pruning command usage"),
+        }
+    }
+}
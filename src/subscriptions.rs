@@ -0,0 +1,39 @@
+use std::fmt;
+use std::str::FromStr;
+
+// What members can subscribe to, to get a DM whenever something new is featured
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Topic {
+    Campaigns,
+    Hackathons,
+    Pathways,
+}
+
+impl Topic {
+    pub const ALL: [Topic; 3] = [Topic::Campaigns, Topic::Hackathons, Topic::Pathways];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Topic::Campaigns => "campaigns",
+            Topic::Hackathons => "hackathons",
+            Topic::Pathways => "pathways",
+        }
+    }
+}
+
+impl fmt::Display for Topic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Topic {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Topic::ALL
+            .into_iter()
+            .find(|topic| topic.as_str().eq_ignore_ascii_case(value.trim()))
+            .ok_or(())
+    }
+}
@@ -1,36 +1,105 @@
+use crate::config::EmbedColors;
 use serenity::builder::{CreateEmbed, CreateMessage, EditMessage};
-use serenity::model::colour::Colour;
 use serenity::model::prelude::*;
 use serenity::prelude::*;
+use std::future::Future;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
 use tokio::time::{sleep, Duration};
+use tracing::error;
+
+// A loading message whose animation runs in the background until it is stopped
+pub struct LoadingMessage {
+    message: Message,
+    stop: oneshot::Sender<()>,
+    animation: JoinHandle<()>,
+}
+
+impl LoadingMessage {
+    // Stop the animation and hand back the message so it can be edited into the final result
+    pub async fn stop(self) -> Message {
+        // The animation finishes any edit it has in flight, so it can't overwrite the result
+        let _ = self.stop.send(());
+        let _ = self.animation.await;
+        self.message
+    }
+}
 
 pub async fn show_loading_animation(
     ctx: &Context,
     msg: &Message,
+    colors: &EmbedColors,
     initial_message: &str,
-) -> Message {
-    let mut embed = CreateEmbed::default()
+) -> serenity::Result<LoadingMessage> {
+    let embed = CreateEmbed::default()
         .description(initial_message)
-        .color(Colour::DARK_BLUE);
+        .color(colors.info);
 
-    let mut message = CreateMessage::default().embed(embed.clone());
+    let message = CreateMessage::default().embed(embed.clone());
 
     // Send initial message
-    let mut loading_msg = msg
-        .channel_id
-        .send_message(&ctx.http, message)
-        .await
-        .unwrap();
-
-    // Create a loading animation
-    let loading_states = ["Loading.", "Loading..", "Loading...", "Loading...."];
-    for state in &loading_states {
-        embed = embed.description(*state);
-        let edit_message = EditMessage::new().embed(embed.clone());
-
-        loading_msg.edit(&ctx.http, edit_message).await.unwrap();
-        sleep(Duration::from_secs(1)).await;
+    let loading_msg = msg.channel_id.send_message(&ctx.http, message).await?;
+
+    // Create a loading animation that keeps cycling until it is told to stop
+    let (stop, mut stopped) = oneshot::channel();
+    let http = ctx.http.clone();
+    let (channel_id, message_id) = (loading_msg.channel_id, loading_msg.id);
+    let animation = tokio::spawn(async move {
+        let loading_states = ["Loading.", "Loading..", "Loading...", "Loading...."];
+        for state in loading_states.iter().cycle() {
+            tokio::select! {
+                _ = &mut stopped => break,
+                _ = sleep(Duration::from_secs(1)) => {}
+            }
+
+            let edit_message = EditMessage::new().embed(embed.clone().description(*state));
+            if channel_id
+                .edit_message(&http, message_id, edit_message)
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+
+    Ok(LoadingMessage {
+        message: loading_msg,
+        stop,
+        animation,
+    })
+}
+
+// Run a request while showing a loading indicator in the channel the message came from.
+// The typing indicator covers short requests; the animated loading embed only appears once
+// the request has taken longer than `threshold`, and stops the moment the request resolves.
+// Returns the request's output and the loading message, if one was sent, to edit into the result.
+pub async fn with_loading_indicator<F: Future>(
+    ctx: &Context,
+    msg: &Message,
+    colors: &EmbedColors,
+    threshold: Duration,
+    request: F,
+) -> (F::Output, Option<Message>) {
+    // Show the typing indicator until the request resolves
+    let _typing = msg.channel_id.start_typing(&ctx.http);
+    tokio::pin!(request);
+
+    // Short requests finish before the threshold and never need the loading embed
+    tokio::select! {
+        output = &mut request => return (output, None),
+        _ = sleep(threshold) => {}
     }
 
-    loading_msg
+    // The request is taking a while, so animate a loading embed until it finishes
+    match show_loading_animation(ctx, msg, colors, "Loading...").await {
+        Ok(loading) => {
+            let output = request.await;
+            (output, Some(loading.stop().await))
+        }
+        Err(why) => {
+            error!(error = ?why, "Error sending loading message");
+            (request.await, None)
+        }
+    }
 }
@@ -0,0 +1,143 @@
+mod common;
+
+use common::*;
+use serenity::model::id::{ChannelId, GuildId};
+use stack_buddy::discord::announcer::Announcer;
+use stack_buddy::guild_settings::Setting;
+use stack_buddy::store::{SqliteStore, Store};
+use stack_buddy::subscriptions::Topic;
+use std::sync::Arc;
+use wiremock::matchers::{method, path};
+use wiremock::Mock;
+
+// A campaign list with a campaign added to the one in the fixture
+const MORE_CAMPAIGNS_JSON: &str = r#"[
+    {"id": 1, "title": "Rust Rising"},
+    {"id": 2, "title": "Web3 Wonders"},
+    {"id": 3, "title": "Cloud Champions", "sub_title": "Ship to the cloud", "quest_count": 4}
+]"#;
+
+// Serve `first` for the first request to an endpoint and `then` for every request after it
+async fn mount_sequence(mock: &MockStackUp, endpoint: &str, first: &str, then: &str) {
+    Mock::given(method("GET"))
+        .and(path(endpoint))
+        .respond_with(json(first))
+        .up_to_n_times(1)
+        .mount(&mock.server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(endpoint))
+        .respond_with(json(then))
+        .mount(&mock.server)
+        .await;
+}
+
+fn announcer_for(mock: &MockStackUp, store: Arc<dyn Store>) -> Announcer {
+    Announcer::new(Arc::new(mock.client()), store, config(mock.config()))
+}
+
+fn store() -> Arc<dyn Store> {
+    Arc::new(SqliteStore::in_memory().unwrap())
+}
+
+#[tokio::test]
+async fn announces_only_what_is_new_since_the_last_poll() {
+    let mock = MockStackUp::empty().await;
+    mount_sequence(
+        &mock,
+        "/stack-featured-campaigns",
+        CAMPAIGNS_JSON,
+        MORE_CAMPAIGNS_JSON,
+    )
+    .await;
+    mount_sequence(&mock, "/stack-featured-hackathons", "[]", HACKATHONS_JSON).await;
+    mount_sequence(
+        &mock,
+        "/stack-featured-pathways",
+        PATHWAYS_JSON,
+        PATHWAYS_JSON,
+    )
+    .await;
+    let announcer = announcer_for(&mock, store());
+
+    // What is already featured when the bot starts isn't announced
+    assert!(announcer.poll().await.is_empty());
+
+    let announcements = announcer.poll().await;
+    let topics: Vec<Topic> = announcements.iter().map(|a| a.topic).collect();
+    assert_eq!(topics, [Topic::Campaigns, Topic::Hackathons]);
+
+    let campaign = &announcements[0].response;
+    assert_eq!(campaign.title.as_deref(), Some("New campaign!"));
+    assert_eq!(campaign.fields[0].name, "Cloud Champions");
+    let hackathon = &announcements[1].response;
+    assert_eq!(hackathon.title.as_deref(), Some("New hackathon!"));
+    assert_eq!(
+        hackathon.url.as_deref(),
+        Some("https://stackup.dev/hackathons/summer-hack")
+    );
+
+    // Nothing changed since, so there is nothing more to announce
+    assert!(announcer.poll().await.is_empty());
+}
+
+#[tokio::test]
+async fn remembers_what_was_seen_through_outages_and_restarts() {
+    let mock = MockStackUp::start().await;
+    let store = store();
+    let announcer = announcer_for(&mock, store.clone());
+    assert!(announcer.poll().await.is_empty());
+
+    // While StackUp is down there is nothing to announce, and once it is back whatever was
+    // added in the meantime is announced
+    mock.server.reset().await;
+    assert!(announcer.poll().await.is_empty());
+    for (endpoint, body) in [
+        ("/stack-featured-campaigns", MORE_CAMPAIGNS_JSON),
+        ("/stack-featured-hackathons", HACKATHONS_JSON),
+        ("/stack-featured-pathways", PATHWAYS_JSON),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(endpoint))
+            .respond_with(json(body))
+            .mount(&mock.server)
+            .await;
+    }
+    // A restart in between doesn't forget what was seen either
+    let announcer = announcer_for(&mock, store);
+    let announcements = announcer.poll().await;
+    assert_eq!(announcements.len(), 1);
+    assert_eq!(announcements[0].response.fields[0].name, "Cloud Champions");
+}
+
+#[tokio::test]
+async fn posts_only_in_the_channels_of_allowed_guilds() {
+    let mock = MockStackUp::empty().await;
+    let store = store();
+    let key = Setting::AnnouncementChannel.key();
+    store
+        .set_guild_setting(GuildId::new(1), key, "10")
+        .await
+        .unwrap();
+    store
+        .set_guild_setting(GuildId::new(2), key, "20")
+        .await
+        .unwrap();
+
+    // With no allowed guilds configured every guild's channel is used
+    let mut config = config(mock.config());
+    config.announcements.channels = vec![ChannelId::new(5)];
+    let announcer = Announcer::new(Arc::new(mock.client()), store.clone(), config.clone());
+    assert_eq!(
+        announcer.channels().await,
+        [ChannelId::new(5), ChannelId::new(10), ChannelId::new(20)]
+    );
+
+    // Otherwise only the allowed guilds' channels are used, alongside the configured ones
+    config.allowed_guilds = vec![GuildId::new(2)];
+    let announcer = Announcer::new(Arc::new(mock.client()), store, config);
+    assert_eq!(
+        announcer.channels().await,
+        [ChannelId::new(5), ChannelId::new(20)]
+    );
+}
@@ -0,0 +1,292 @@
+mod common;
+
+use common::*;
+use reqwest::header::HeaderValue;
+use reqwest::StatusCode;
+use stack_buddy::api::{StackUpApi, StackUpApiError};
+use stack_buddy::cache::ApiCache;
+use stack_buddy::config::CacheConfig;
+use stack_buddy::models::Money;
+use std::sync::Arc;
+use std::time::Duration;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+#[tokio::test]
+async fn decodes_user_details() {
+    let mock = MockStackUp::start().await;
+    let user = mock.client().get_user_details(USER_ID).await.unwrap();
+
+    assert_eq!(user.id, USER_ID);
+    assert_eq!(user.username, "stackie42");
+    assert_eq!(user.tech_stack, ["Rust", "TypeScript"]);
+}
+
+#[tokio::test]
+async fn decodes_user_balance() {
+    let mock = MockStackUp::start().await;
+    let balance = mock.client().get_user_balance(USER_ID).await.unwrap();
+
+    assert_eq!(balance.current_balance, Money::usd(25000));
+    assert_eq!(balance.total_earnings, Money::usd(35050));
+}
+
+#[tokio::test]
+async fn decodes_user_progress() {
+    let mock = MockStackUp::start().await;
+    let progress = mock.client().get_user_progress(USER_ID).await.unwrap();
+
+    assert_eq!(progress.submissions, 12);
+    assert_eq!(progress.total_quest_earnings, Money::usd(35000));
+}
+
+#[tokio::test]
+async fn decodes_featured_lists() {
+    let mock = MockStackUp::start().await;
+    let client = mock.client();
+
+    let campaigns = client.get_featured_campaigns().await.unwrap();
+    assert_eq!(campaigns.len(), 2);
+    assert_eq!(campaigns[0].title, "Rust Rising");
+
+    let pathways = client.get_pathways().await.unwrap();
+    assert_eq!(pathways[0].title, "Backend Foundations");
+
+    let hackathons = client.get_hackathons().await.unwrap();
+    assert_eq!(hackathons[0].participating, 120);
+}
+
+#[tokio::test]
+async fn maps_missing_user_to_not_found() {
+    let mock = MockStackUp::start().await;
+    let err = mock
+        .client()
+        .get_user_balance(MISSING_USER_ID)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, StackUpApiError::NotFound), "{:?}", err);
+    assert_eq!(
+        err.reason(),
+        "StackUp couldn't find that account. It may no longer exist."
+    );
+}
+
+#[tokio::test]
+async fn maps_forbidden_to_unauthorized() {
+    let mock = MockStackUp::start().await;
+    let err = mock
+        .client()
+        .get_user_details(FORBIDDEN_USER_ID)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, StackUpApiError::Unauthorized), "{:?}", err);
+}
+
+#[tokio::test]
+async fn gives_up_on_long_retry_after() {
+    let mock = MockStackUp::start().await;
+    let err = mock
+        .client()
+        .get_user_progress(RATE_LIMITED_USER_ID)
+        .await
+        .unwrap_err();
+
+    // Waiting two minutes is longer than the retry limit, so it isn't retried
+    assert!(
+        matches!(
+            err,
+            StackUpApiError::RateLimited {
+                retry_after: Some(retry_after)
+            } if retry_after == Duration::from_secs(120)
+        ),
+        "{:?}",
+        err
+    );
+    assert_eq!(mock.request_count().await, 1);
+}
+
+#[tokio::test]
+async fn keeps_a_snippet_of_malformed_bodies() {
+    let mock = MockStackUp::start().await;
+    let err = mock
+        .client()
+        .get_user_details(MALFORMED_USER_ID)
+        .await
+        .unwrap_err();
+
+    match err {
+        StackUpApiError::Decode { body, .. } => assert!(body.contains("not a number")),
+        err => panic!("expected a decode error, got {:?}", err),
+    }
+}
+
+#[tokio::test]
+async fn retries_server_errors_before_giving_up() {
+    let mock = MockStackUp::start().await;
+    let err = mock
+        .client()
+        .get_user_balance(BROKEN_USER_ID)
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(
+            err,
+            StackUpApiError::Http(StatusCode::INTERNAL_SERVER_ERROR)
+        ),
+        "{:?}",
+        err
+    );
+    // The first attempt plus two retries
+    assert_eq!(mock.request_count().await, 3);
+}
+
+#[tokio::test]
+async fn recovers_when_a_retry_succeeds() {
+    let mock = MockStackUp::empty().await;
+    Mock::given(method("GET"))
+        .and(path("/stack-featured-pathways"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&mock.server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/stack-featured-pathways"))
+        .respond_with(json(PATHWAYS_JSON))
+        .mount(&mock.server)
+        .await;
+
+    let pathways = mock.client().get_pathways().await.unwrap();
+    assert_eq!(pathways.len(), 1);
+    assert_eq!(mock.request_count().await, 2);
+}
+
+#[tokio::test]
+async fn short_circuits_while_the_backend_is_down() {
+    let mock = MockStackUp::start().await;
+    let mut config = mock.config();
+    config.max_retries = 0;
+    config.breaker_threshold = 2;
+    let client = mock.client_with(config);
+
+    for _ in 0..2 {
+        let err = client.get_user_details(BROKEN_USER_ID).await.unwrap_err();
+        assert!(matches!(err, StackUpApiError::Http(_)), "{:?}", err);
+    }
+
+    // The breaker is now open, so StackUp isn't called again
+    let err = client.get_user_details(USER_ID).await.unwrap_err();
+    assert!(
+        matches!(err, StackUpApiError::Unavailable { .. }),
+        "{:?}",
+        err
+    );
+    assert_eq!(mock.request_count().await, 2);
+}
+
+#[tokio::test]
+async fn rate_limits_dont_reset_the_breaker() {
+    let mock = MockStackUp::start().await;
+    let mut config = mock.config();
+    config.max_retries = 0;
+    config.breaker_threshold = 2;
+    let client = mock.client_with(config);
+
+    // A rate limit between two failures doesn't count as StackUp being back up
+    client.get_user_details(BROKEN_USER_ID).await.unwrap_err();
+    let err = client
+        .get_user_details(RATE_LIMITED_USER_ID)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, StackUpApiError::RateLimited { .. }),
+        "{:?}",
+        err
+    );
+    client.get_user_details(BROKEN_USER_ID).await.unwrap_err();
+
+    let err = client.get_user_details(USER_ID).await.unwrap_err();
+    assert!(
+        matches!(err, StackUpApiError::Unavailable { .. }),
+        "{:?}",
+        err
+    );
+    assert_eq!(mock.request_count().await, 3);
+}
+
+#[tokio::test]
+async fn reports_unreachable_backends_as_network_errors() {
+    // Nothing listens on a port once the listener holding it is dropped
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let mut config = api_config(&format!("http://127.0.0.1:{}", port));
+    config.max_retries = 0;
+    let client = stack_buddy::api::StackUpClient::new(&config).unwrap();
+
+    let err = client.get_hackathons().await.unwrap_err();
+    assert!(matches!(err, StackUpApiError::Network(_)), "{:?}", err);
+}
+
+#[tokio::test]
+async fn sends_the_auth_token() {
+    let mock = MockStackUp::empty().await;
+    Mock::given(method("GET"))
+        .and(path("/stack-featured-campaigns"))
+        .and(header("authorization", "Bearer secret"))
+        .respond_with(json(CAMPAIGNS_JSON))
+        .mount(&mock.server)
+        .await;
+
+    let mut config = mock.config();
+    config.auth_token = Some(HeaderValue::from_static("Bearer secret"));
+    let campaigns = mock
+        .client_with(config)
+        .get_featured_campaigns()
+        .await
+        .unwrap();
+    assert_eq!(campaigns.len(), 2);
+}
+
+#[tokio::test]
+async fn cache_answers_repeated_requests() {
+    let mock = MockStackUp::start().await;
+    let cache_config = CacheConfig {
+        stale_while_revalidate: Duration::from_secs(60),
+        campaigns_ttl: Duration::from_secs(60),
+        pathways_ttl: Duration::from_secs(60),
+        hackathons_ttl: Duration::from_secs(60),
+        balance_ttl: Duration::from_secs(60),
+        progress_ttl: Duration::from_secs(60),
+        user_details_ttl: Duration::ZERO,
+    };
+    let cache = ApiCache::new(Arc::new(mock.client()), &cache_config);
+
+    for _ in 0..3 {
+        cache.get_featured_campaigns().await.unwrap();
+        cache.get_user_balance(USER_ID).await.unwrap();
+    }
+    assert_eq!(mock.request_count().await, 2);
+
+    // A TTL of zero turns caching off for that endpoint
+    for _ in 0..2 {
+        cache.get_user_details(USER_ID).await.unwrap();
+    }
+    assert_eq!(mock.request_count().await, 4);
+
+    // Errors are never cached
+    for _ in 0..2 {
+        cache.get_user_balance(MISSING_USER_ID).await.unwrap_err();
+    }
+    assert_eq!(mock.request_count().await, 6);
+
+    let stats = cache.stats();
+    let (_, campaigns) = stats.iter().find(|(name, _)| *name == "campaigns").unwrap();
+    assert_eq!((campaigns.hits, campaigns.misses), (2, 1));
+
+    assert_eq!(cache.clear(), 2);
+}
@@ -0,0 +1,60 @@
+use stack_buddy::discord::arguments::{parse_args, ArgError, ParsedArgs};
+
+fn flag<'a>(args: &'a ParsedArgs, name: &str) -> Option<Option<&'a str>> {
+    args.flags.get(name).map(Option::as_deref)
+}
+
+#[test]
+fn splits_positional_arguments_and_flags_in_any_order() {
+    let args = parse_args("one --limit 5 two --verbose --sort=name three", &[]).unwrap();
+    assert_eq!(args.positional, ["one", "two", "three"]);
+    assert_eq!(flag(&args, "limit"), Some(Some("5")));
+    assert_eq!(flag(&args, "sort"), Some(Some("name")));
+    // A flag followed by another flag or nothing is a switch
+    assert_eq!(flag(&args, "verbose"), Some(None));
+
+    let args = parse_args("--limit 5 --VERBOSE", &[]).unwrap();
+    assert!(args.positional.is_empty());
+    assert_eq!(flag(&args, "verbose"), Some(None));
+}
+
+#[test]
+fn keeps_quoted_text_together_and_never_treats_it_as_a_flag() {
+    let args = parse_args(
+        r#""Rust Rising" --title “Web3 Wonders” "--not-a-flag""#,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(args.positional, ["Rust Rising", "--not-a-flag"]);
+    assert_eq!(flag(&args, "title"), Some(Some("Web3 Wonders")));
+
+    // A quoted value after a flag is still its value, even if it looks like a flag
+    let args = parse_args(r#"--title "--dashes""#, &[]).unwrap();
+    assert_eq!(flag(&args, "title"), Some(Some("--dashes")));
+
+    assert!(matches!(
+        parse_args(r#"--title "unclosed"#, &[]),
+        Err(ArgError::UnclosedQuote)
+    ));
+}
+
+#[test]
+fn switches_never_take_the_next_argument() {
+    // Whatever follows a switch stays a positional argument, wherever the switch is
+    let args = parse_args("--private <@42>", &["private"]).unwrap();
+    assert_eq!(args.positional, ["<@42>"]);
+    assert_eq!(flag(&args, "private"), Some(None));
+    let args = parse_args("<@42> --Private", &["private"]).unwrap();
+    assert_eq!(args.positional, ["<@42>"]);
+    assert_eq!(flag(&args, "private"), Some(None));
+
+    let args = parse_args("--private false", &["private"]).unwrap();
+    assert_eq!(args.positional, ["false"]);
+
+    // Giving a switch a value is an error rather than silently ignored
+    let err = parse_args("--private=false", &["private"]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`--private` is a switch and doesn't take a value."
+    );
+}
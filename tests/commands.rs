@@ -0,0 +1,602 @@
+mod common;
+
+use common::*;
+use serenity::model::colour::Colour;
+use serenity::model::id::{ChannelId, GuildId, UserId};
+use serenity::model::permissions::Permissions;
+use stack_buddy::discord::commands::{command_help, CommandRegistry};
+use stack_buddy::discord::messages::NOT_LINKED_MESSAGE;
+use stack_buddy::discord::paginator::{PageButton, Paginator};
+use stack_buddy::discord::response::{Field, Response};
+use stack_buddy::guild_settings::GuildSettings;
+use stack_buddy::subscriptions::Topic;
+
+#[test]
+fn splits_long_responses_into_pages() {
+    let fields = (1..=7).map(|n| Field::new(format!("Item {}", n), "details", false));
+    let response = Response::new("**Items:**", Colour::BLUE)
+        .fields(fields)
+        .paginate(3);
+
+    let pages = response.pages();
+    assert_eq!(pages.len(), 3);
+    assert_eq!(
+        pages
+            .iter()
+            .map(|page| page.fields.len())
+            .collect::<Vec<_>>(),
+        [3, 3, 1]
+    );
+    assert_eq!(pages[0].description, "**Items:**");
+    assert_eq!(pages[0].footer.as_deref(), Some("Page 1 of 3"));
+    assert_eq!(pages[2].fields[0].name, "Item 7");
+
+    // Responses that fit on one page aren't split
+    let short = Response::new("**Items:**", Colour::BLUE)
+        .fields([Field::new("Only", "one", false)])
+        .paginate(3);
+    let pages = short.pages();
+    assert_eq!(pages.len(), 1);
+    assert_eq!(pages[0], short);
+}
+
+#[test]
+fn turns_pages_with_the_buttons() {
+    let pages = Response::new("**Items:**", Colour::BLUE)
+        .fields((1..=7).map(|n| Field::new(format!("Item {}", n), "details", false)))
+        .paginate(2)
+        .pages();
+    let mut paginator = Paginator::new(pages);
+
+    // Nothing comes before the first page
+    assert!(!paginator.is_enabled(PageButton::Previous));
+    assert!(!paginator.press(PageButton::First));
+
+    assert!(paginator.press(PageButton::Next));
+    assert_eq!(paginator.current(), 1);
+    assert!(paginator.press(PageButton::Last));
+    assert_eq!(paginator.page().footer.as_deref(), Some("Page 4 of 4"));
+
+    // Nothing comes after the last page
+    assert!(!paginator.is_enabled(PageButton::Next));
+    assert!(!paginator.press(PageButton::Next));
+
+    assert!(paginator.press(PageButton::Previous));
+    assert_eq!(paginator.current(), 2);
+    assert!(paginator.press(PageButton::First));
+    assert_eq!(paginator.current(), 0);
+
+    assert_eq!(
+        PageButton::from_custom_id(PageButton::Last.custom_id()),
+        Some(PageButton::Last)
+    );
+    assert_eq!(PageButton::from_custom_id("something-else"), None);
+}
+
+#[test]
+fn help_lists_only_permitted_commands() {
+    let registry = CommandRegistry::default();
+
+    let settings = GuildSettings::default();
+
+    let help = registry.help_message(&caller(1, Permissions::empty()), &settings);
+    assert!(help.contains("`!link <stackup-id>`"), "{}", help);
+    assert!(
+        help.contains("`!balance [@member] [--private]`"),
+        "{}",
+        help
+    );
+    assert!(!help.contains("`!config"), "{}", help);
+    assert!(!help.contains("cache-clear"), "{}", help);
+
+    // Server admins get the server's commands, but the cache is only the owners' to manage
+    let mut admin = caller(1, Permissions::ADMINISTRATOR);
+    admin.prefix = "?".to_string();
+    let help = registry.help_message(&admin, &settings);
+    assert!(help.contains("`?config"), "{}", help);
+    assert!(!help.contains("cache-clear"), "{}", help);
+
+    let help = registry.help_message(&caller(OWNER_ID, Permissions::empty()), &settings);
+    assert!(help.contains("`!cache-clear`"), "{}", help);
+}
+
+#[tokio::test]
+async fn help_leaves_out_commands_the_server_turned_off() {
+    let mock = MockStackUp::start().await;
+    let handler = handler(&mock);
+    let guild = Some(GuildId::new(9));
+    let channel = ChannelId::new(10);
+    let admin = handler
+        .caller(UserId::new(1), guild, channel, Permissions::MANAGE_GUILD, 7)
+        .await;
+    run(&handler, &admin, "config set disabled-commands hackathons").await;
+
+    let member = handler
+        .caller(UserId::new(2), guild, channel, Permissions::empty(), 7)
+        .await;
+    let help = run(&handler, &member, "help").await;
+    assert!(
+        !help.description.contains("`!hackathons"),
+        "{}",
+        help.description
+    );
+    assert!(
+        help.description.contains("`!pathways"),
+        "{}",
+        help.description
+    );
+
+    let help = run(&handler, &member, "help hackathons").await;
+    assert_eq!(
+        help.description,
+        "Unknown command `hackathons`. Use `/help` to see every command."
+    );
+
+    // Other servers still have it
+    let elsewhere = handler
+        .caller(
+            UserId::new(2),
+            Some(GuildId::new(8)),
+            channel,
+            Permissions::empty(),
+            7,
+        )
+        .await;
+    let help = run(&handler, &elsewhere, "help").await;
+    assert!(
+        help.description.contains("`!hackathons"),
+        "{}",
+        help.description
+    );
+}
+
+#[tokio::test]
+async fn suggests_only_commands_the_caller_can_run() {
+    let mock = MockStackUp::start().await;
+    let handler = handler(&mock);
+    let help = handler.commands.find("help").unwrap();
+    let guild = Some(GuildId::new(9));
+    let channel = ChannelId::new(10);
+    let admin = handler
+        .caller(
+            UserId::new(1),
+            guild,
+            channel,
+            Permissions::ADMINISTRATOR,
+            7,
+        )
+        .await;
+    run(&handler, &admin, "config set prefix ?").await;
+    run(&handler, &admin, "config set disabled-commands campaigns").await;
+
+    // The server's prefix is ignored like the slash, and disabled commands are left out
+    let member = handler
+        .caller(UserId::new(2), guild, channel, Permissions::empty(), 7)
+        .await;
+    assert_eq!(
+        help.autocomplete(&handler, &member, "?ca").await,
+        ["calendar"]
+    );
+    assert_eq!(help.autocomplete(&handler, &member, "/he").await, ["help"]);
+
+    // Commands are only suggested to members with the permissions they need, and the cache
+    // commands only to the bot's owners
+    assert!(help.autocomplete(&handler, &member, "con").await.is_empty());
+    assert_eq!(help.autocomplete(&handler, &admin, "con").await, ["config"]);
+    assert_eq!(
+        help.autocomplete(&handler, &admin, "ca").await,
+        ["calendar"]
+    );
+    let owner = handler
+        .caller(
+            UserId::new(OWNER_ID),
+            guild,
+            channel,
+            Permissions::empty(),
+            7,
+        )
+        .await;
+    assert_eq!(
+        help.autocomplete(&handler, &owner, "ca").await,
+        ["calendar", "cache-stats", "cache-clear"]
+    );
+}
+
+#[test]
+fn renders_detailed_help_for_every_command() {
+    let registry = CommandRegistry::default();
+    for command in registry.iter() {
+        let help = command_help(command, "!");
+        assert!(help.contains(command.description()), "{}", help);
+        assert!(
+            help.contains(&format!("Also available as **`/{}`**", command.name())),
+            "{}",
+            help
+        );
+        for example in command.examples() {
+            let shown = if example.starts_with('/') {
+                example.to_string()
+            } else {
+                format!("!{}", example)
+            };
+            assert!(help.contains(&shown), "{}", help);
+        }
+    }
+}
+
+#[tokio::test]
+async fn responds_with_the_callers_balance_once_linked() {
+    let mock = MockStackUp::start().await;
+    let handler = handler(&mock);
+    let caller = caller(1, Permissions::empty());
+
+    // Nothing can be looked up before the account is linked
+    let response = run(&handler, &caller, "balance").await;
+    assert_eq!(
+        response,
+        Response::new(NOT_LINKED_MESSAGE, handler.config.embed.colors.warning)
+    );
+
+    let response = run(&handler, &caller, &format!("link {}", USER_ID)).await;
+    assert_eq!(response.color, handler.config.embed.colors.success);
+
+    let response = run(&handler, &caller, "balance").await;
+    assert_eq!(response.description, "Your StackUp balance");
+    assert_eq!(response.color, handler.config.embed.colors.balance);
+    assert_eq!(response.fields[0].value, "$250");
+    assert!(!response.direct);
+
+    // The private breakdown is the same, only sent to the caller's DMs
+    let private = run(&handler, &caller, "balance --private").await;
+    assert!(private.direct);
+    assert_eq!(private.fields, response.fields);
+}
+
+#[tokio::test]
+async fn hides_other_members_private_balances() {
+    let mock = MockStackUp::start().await;
+    let handler = handler(&mock);
+    let owner = caller(1, Permissions::empty());
+    let other = caller(2, Permissions::empty());
+    run(&handler, &owner, &format!("link {}", USER_ID)).await;
+
+    let response = run(&handler, &other, "balance <@1>").await;
+    assert_eq!(
+        response.description,
+        "<@1> keeps their StackUp balance private."
+    );
+
+    run(&handler, &owner, "privacy public").await;
+    let response = run(&handler, &other, "balance <@1>").await;
+    assert_eq!(response.description, "<@1>'s StackUp balance");
+    assert_eq!(response.fields[0].value, "$250");
+    // `--private` is a switch, so the member after it is still the one looked up
+    let response = run(&handler, &other, "balance --private <@1>").await;
+    assert_eq!(response.description, "<@1>'s StackUp balance");
+    assert!(response.direct);
+}
+
+#[tokio::test]
+async fn reports_api_errors_with_a_correlation_id() {
+    let mock = MockStackUp::start().await;
+    let handler = handler(&mock);
+    let caller = caller(1, Permissions::empty());
+    run(&handler, &caller, &format!("link {}", MISSING_USER_ID)).await;
+
+    let response = run(&handler, &caller, "profile").await;
+    assert_eq!(response.title.as_deref(), Some("Something went wrong"));
+    assert_eq!(response.footer.as_deref(), Some("Correlation ID: 7"));
+    assert_eq!(response.color, handler.config.embed.colors.error);
+    assert!(
+        response.description.contains("couldn't find that account"),
+        "{:?}",
+        response
+    );
+}
+
+#[tokio::test]
+async fn responds_with_featured_lists() {
+    let mock = MockStackUp::start().await;
+    let handler = handler(&mock);
+    let caller = caller(1, Permissions::empty());
+
+    let response = run(&handler, &caller, "campaigns").await;
+    assert_eq!(response.color, handler.config.embed.colors.campaigns);
+    assert_eq!(response.fields[0].name, "Rust Rising");
+    assert_eq!(response.page_size, Some(handler.config.embed.page_size));
+}
+
+#[tokio::test]
+async fn responds_with_a_usage_hint_for_bad_arguments() {
+    let mock = MockStackUp::start().await;
+    let handler = handler(&mock);
+
+    let response = run(&handler, &caller(1, Permissions::empty()), "link abc").await;
+    assert_eq!(response.color, handler.config.embed.colors.warning);
+    assert!(
+        response
+            .description
+            .contains("**Usage:** `!link <stackup-id>`"),
+        "{:?}",
+        response
+    );
+}
+
+#[tokio::test]
+async fn opens_featured_lists_on_the_requested_page() {
+    let mock = MockStackUp::start().await;
+    let handler = handler(&mock);
+    let caller = caller(1, Permissions::empty());
+
+    let response = run(&handler, &caller, "get-hackathons 2").await;
+    assert_eq!(response.start_page, 1);
+    let response = run(&handler, &caller, "pathways").await;
+    assert_eq!(response.start_page, 0);
+
+    for page in ["0", "two"] {
+        let response = run(&handler, &caller, &format!("campaigns {}", page)).await;
+        assert!(
+            response
+                .description
+                .contains("**Usage:** `!campaigns [page]`"),
+            "{}",
+            response.description
+        );
+    }
+}
+
+#[tokio::test]
+async fn checks_permissions_before_running_commands() {
+    let mock = MockStackUp::start().await;
+    let handler = handler(&mock);
+
+    let response = run(&handler, &caller(1, Permissions::empty()), "config get").await;
+    assert_eq!(
+        response.description,
+        "You need the **Manage Guild** permission to use this command."
+    );
+
+    // The cache is shared by every server, so not even server admins can clear it
+    for permissions in [Permissions::empty(), Permissions::ADMINISTRATOR] {
+        let response = run(&handler, &caller(1, permissions), "cache-clear").await;
+        assert_eq!(
+            response.description,
+            "Only the bot's owners can use this command."
+        );
+    }
+
+    let response = run(
+        &handler,
+        &caller(OWNER_ID, Permissions::empty()),
+        "cache-clear",
+    )
+    .await;
+    assert_eq!(response.description, "Cleared **0** cached responses.");
+}
+
+#[test]
+fn renders_responses_as_embeds() {
+    let response = Response::new("Hello", Colour::DARK_GREEN)
+        .title("Greeting")
+        .image("https://example.com/image.png")
+        .footer("Bye");
+    let embed = serde_json::to_value(response.to_embed("https://example.com/thumb.png")).unwrap();
+
+    assert_eq!(embed["description"], "Hello");
+    assert_eq!(embed["title"], "Greeting");
+    assert_eq!(embed["image"]["url"], "https://example.com/image.png");
+    assert_eq!(embed["thumbnail"]["url"], "https://example.com/thumb.png");
+    assert_eq!(embed["footer"]["text"], "Bye");
+    assert_eq!(embed["author"]["name"], "Stack-Buddy");
+}
+
+#[tokio::test]
+async fn subscribes_to_new_items() {
+    let mock = MockStackUp::start().await;
+    let handler = handler(&mock);
+    let caller = caller(1, Permissions::empty());
+
+    let response = run(&handler, &caller, "subscribe Hackathons").await;
+    assert_eq!(response.color, handler.config.embed.colors.success);
+    let response = run(&handler, &caller, "subscribe hackathons").await;
+    assert_eq!(
+        response.description,
+        "You're already subscribed to new hackathons."
+    );
+    assert_eq!(
+        handler.store.subscribers(Topic::Hackathons).await.unwrap(),
+        [caller.id]
+    );
+    assert!(handler
+        .store
+        .subscribers(Topic::Campaigns)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let response = run(&handler, &caller, "unsubscribe hackathons").await;
+    assert_eq!(
+        response.description,
+        "You'll no longer get DMs about new hackathons."
+    );
+    assert!(handler.store.topics(caller.id).await.unwrap().is_empty());
+
+    // Unknown topics get the usage hint
+    let response = run(&handler, &caller, "subscribe quests").await;
+    assert_eq!(response.color, handler.config.embed.colors.warning);
+    assert!(response
+        .description
+        .contains("`campaigns`, `hackathons` or `pathways`"));
+}
+
+#[tokio::test]
+async fn changes_guild_settings() {
+    let mock = MockStackUp::start().await;
+    let handler = handler(&mock);
+    let guild = Some(GuildId::new(9));
+    let channel = ChannelId::new(10);
+    let admin = handler
+        .caller(UserId::new(1), guild, channel, Permissions::MANAGE_GUILD, 7)
+        .await;
+
+    // Only members who can manage the server can change its settings
+    let member = handler
+        .caller(UserId::new(2), guild, channel, Permissions::empty(), 7)
+        .await;
+    let response = run(&handler, &member, "config set prefix ?").await;
+    assert!(
+        response.description.contains("Manage Guild"),
+        "{}",
+        response.description
+    );
+
+    let response = run(&handler, &admin, "config set prefix ?").await;
+    assert_eq!(response.description, "**prefix** is now `?`.");
+    let response = run(&handler, &admin, "config set accent-color 0x123456").await;
+    assert_eq!(response.description, "**accent-color** is now `#123456`.");
+    let response = run(&handler, &admin, "config set disabled-commands get-balance").await;
+    assert_eq!(
+        response.description,
+        "**disabled-commands** is now `balance`."
+    );
+    let response = run(&handler, &admin, "config set allowed-channels <#10>, 11").await;
+    assert_eq!(
+        response.description,
+        "**allowed-channels** is now <#10>, <#11>."
+    );
+
+    // Values that don't fit the setting get the usage hint
+    let response = run(&handler, &admin, "config set accent-color purple").await;
+    assert!(
+        response.description.contains("a color, like `#1F8B4C`"),
+        "{}",
+        response.description
+    );
+    let response = run(&handler, &admin, "config set disabled-commands config").await;
+    assert!(
+        response.description.contains("other than `config`"),
+        "{}",
+        response.description
+    );
+
+    // The settings apply to everyone in the server
+    let member = handler
+        .caller(UserId::new(2), guild, channel, Permissions::empty(), 7)
+        .await;
+    assert_eq!(member.prefix, "?");
+    assert_eq!(member.colors.balance, Colour::new(0x123456));
+    assert_eq!(member.colors.warning, handler.config.embed.colors.warning);
+    let response = run(&handler, &member, "balance").await;
+    assert_eq!(
+        response.description,
+        "`?balance` is turned off in this server."
+    );
+    let elsewhere = handler
+        .caller(
+            UserId::new(2),
+            guild,
+            ChannelId::new(12),
+            Permissions::empty(),
+            7,
+        )
+        .await;
+    let response = run(&handler, &elsewhere, "help").await;
+    assert_eq!(
+        response.description,
+        "Commands can only be used in <#10>, <#11> in this server."
+    );
+
+    let response = run(&handler, &admin, "config get prefix").await;
+    assert_eq!(response.description, "**prefix:** `?`");
+    let response = run(&handler, &admin, "config reset prefix").await;
+    assert_eq!(response.description, "**prefix** is back to its default.");
+    let response = run(&handler, &admin, "config get prefix").await;
+    assert_eq!(response.description, "**prefix:** `!` *(default)*");
+
+    // Other servers and DMs keep the defaults
+    let dm = handler
+        .caller(UserId::new(1), None, channel, Permissions::MANAGE_GUILD, 7)
+        .await;
+    assert_eq!(dm.colors.balance, handler.config.embed.colors.balance);
+    let response = run(&handler, &dm, "config get").await;
+    assert_eq!(
+        response.description,
+        "Settings can only be changed in a server."
+    );
+}
+
+#[tokio::test]
+async fn sends_private_responses_to_dms_with_a_notice_in_the_server() {
+    let mock = MockStackUp::start().await;
+    let handler = handler(&mock);
+    let response = Response::new("Your StackUp balance", Colour::BLUE).direct(true);
+    let colors = &handler.config.embed.colors;
+
+    let responder = RecordingResponder::default();
+    handler
+        .send_response(&responder, Some(GuildId::new(9)), &response)
+        .await;
+    assert_eq!(
+        responder.sent(),
+        [
+            Sent::DirectMessage(response.clone()),
+            Sent::Edit(Response::new(
+                "📬 Sent you the details in a direct message.",
+                colors.success
+            )),
+            Sent::Reaction('📬'),
+        ]
+    );
+
+    // Members with closed DMs are told how to open them instead
+    let responder = RecordingResponder {
+        dms_closed: true,
+        ..Default::default()
+    };
+    handler
+        .send_response(&responder, Some(GuildId::new(9)), &response)
+        .await;
+    let sent = responder.sent();
+    assert!(
+        matches!(&sent[..], [Sent::Edit(notice)] if notice.color == colors.warning
+            && notice.description.contains("privacy settings")),
+        "{:?}",
+        sent
+    );
+
+    // Commands run in DMs are already private, so they are answered the usual way
+    let responder = RecordingResponder::default();
+    handler.send_response(&responder, None, &response).await;
+    assert_eq!(responder.sent(), [Sent::Edit(response)]);
+}
+
+#[tokio::test]
+async fn sends_long_responses_as_pages() {
+    let mock = MockStackUp::start().await;
+    let handler = handler(&mock);
+    let fields = (1..=7).map(|n| Field::new(format!("Item {}", n), "details", false));
+    let response = Response::new("**Items:**", Colour::BLUE)
+        .fields(fields)
+        .paginate(3);
+
+    let responder = RecordingResponder::default();
+    handler.send_response(&responder, None, &response).await;
+    assert_eq!(responder.sent(), [Sent::Pages(response.pages())]);
+
+    // Lists can start on a later page, or on the last one when asked for one past it
+    for (start, first) in [(1, 1), (9, 2)] {
+        let responder = RecordingResponder::default();
+        let response = response.clone().start_at(start);
+        handler.send_response(&responder, None, &response).await;
+        assert_eq!(
+            responder.sent(),
+            [Sent::Pages(response.pages()[first..].to_vec())]
+        );
+    }
+
+    // Short responses replace the pending message as they are
+    let short = Response::new("**Items:**", Colour::BLUE).paginate(3);
+    let responder = RecordingResponder::default();
+    handler.send_response(&responder, None, &short).await;
+    assert_eq!(responder.sent(), [Sent::Edit(short)]);
+}
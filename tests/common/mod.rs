@@ -0,0 +1,332 @@
+// Shared fixtures for the integration tests: an in-process mock of the StackUp API
+#![allow(dead_code)]
+
+use async_trait::async_trait;
+use serenity::model::colour::Colour;
+use serenity::model::id::{ChannelId, UserId};
+use serenity::model::permissions::Permissions;
+use stack_buddy::api::StackUpClient;
+use stack_buddy::cache::ApiCache;
+use stack_buddy::config::{
+    AnnouncementConfig, ApiConfig, CacheConfig, Config, CooldownConfig, EmbedColors, EmbedConfig,
+    LogFormat, LogLevel, Rate, Secret,
+};
+use stack_buddy::cooldowns::Cooldowns;
+use stack_buddy::discord::arguments::{parse_args, CommandArgs};
+use stack_buddy::discord::commands::CommandRegistry;
+use stack_buddy::discord::discord::{Caller, Handler};
+use stack_buddy::discord::paginator::{PageButton, Paginator};
+use stack_buddy::discord::responder::Responder;
+use stack_buddy::discord::response::Response;
+use stack_buddy::guild_settings::GuildSettingsCache;
+use stack_buddy::store::{SqliteStore, Store};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+// The Discord user ID of the bot's owner in the test config
+pub const OWNER_ID: u64 = 100;
+
+// The StackUp user ID the canned user responses belong to
+pub const USER_ID: i32 = 42;
+// User IDs that make the mock answer with an error instead
+pub const MISSING_USER_ID: i32 = 404;
+pub const FORBIDDEN_USER_ID: i32 = 401;
+pub const RATE_LIMITED_USER_ID: i32 = 429;
+pub const BROKEN_USER_ID: i32 = 500;
+pub const MALFORMED_USER_ID: i32 = 999;
+
+pub const USER_JSON: &str = include_str!("../fixtures/user.json");
+pub const BALANCE_JSON: &str = include_str!("../fixtures/balance.json");
+pub const PROGRESS_JSON: &str = include_str!("../fixtures/progress.json");
+pub const CAMPAIGNS_JSON: &str = include_str!("../fixtures/campaigns.json");
+pub const PATHWAYS_JSON: &str = include_str!("../fixtures/pathways.json");
+pub const HACKATHONS_JSON: &str = include_str!("../fixtures/hackathons.json");
+
+// A mock StackUp backend serving the canned fixtures, plus error and malformed responses
+pub struct MockStackUp {
+    pub server: MockServer,
+}
+
+impl MockStackUp {
+    // Start a mock backend with every endpoint mounted
+    pub async fn start() -> Self {
+        let server = MockServer::start().await;
+
+        // The canned responses for the user with ID `USER_ID` and the featured lists
+        let canned = [
+            (format!("/get-user/{}", USER_ID), USER_JSON),
+            (format!("/get-user-balance/{}", USER_ID), BALANCE_JSON),
+            (format!("/get-user-progress/{}", USER_ID), PROGRESS_JSON),
+            ("/stack-featured-campaigns".to_string(), CAMPAIGNS_JSON),
+            ("/stack-featured-pathways".to_string(), PATHWAYS_JSON),
+            ("/stack-featured-hackathons".to_string(), HACKATHONS_JSON),
+        ];
+        for (endpoint, body) in canned {
+            Mock::given(method("GET"))
+                .and(path(endpoint))
+                .respond_with(json(body))
+                .mount(&server)
+                .await;
+        }
+
+        // Error responses, keyed by user ID, for every per-user endpoint
+        for endpoint in ["get-user", "get-user-balance", "get-user-progress"] {
+            let errors = [
+                (MISSING_USER_ID, ResponseTemplate::new(404)),
+                (FORBIDDEN_USER_ID, ResponseTemplate::new(401)),
+                (
+                    RATE_LIMITED_USER_ID,
+                    ResponseTemplate::new(429).insert_header("Retry-After", "120"),
+                ),
+                (BROKEN_USER_ID, ResponseTemplate::new(500)),
+                (MALFORMED_USER_ID, json("{\"id\": \"not a number\"")),
+            ];
+            for (user_id, response) in errors {
+                Mock::given(method("GET"))
+                    .and(path(format!("/{}/{}", endpoint, user_id)))
+                    .respond_with(response)
+                    .mount(&server)
+                    .await;
+            }
+        }
+
+        Self { server }
+    }
+
+    // Start a mock backend with nothing mounted, for tests that set up their own responses
+    pub async fn empty() -> Self {
+        Self {
+            server: MockServer::start().await,
+        }
+    }
+
+    // Create a client for the mock backend with the test API config
+    pub fn client(&self) -> StackUpClient {
+        self.client_with(self.config())
+    }
+
+    // Create a client for the mock backend with a customised API config
+    pub fn client_with(&self, config: ApiConfig) -> StackUpClient {
+        StackUpClient::new(&config).expect("Err creating StackUp client")
+    }
+
+    // An API config pointing at the mock backend, with quick retries so tests stay fast
+    pub fn config(&self) -> ApiConfig {
+        api_config(&self.server.uri())
+    }
+
+    // How many requests the mock backend has received so far
+    pub async fn request_count(&self) -> usize {
+        self.server
+            .received_requests()
+            .await
+            .map_or(0, |requests| requests.len())
+    }
+}
+
+// An API config for the given base URL, with quick retries so tests stay fast
+pub fn api_config(base_url: &str) -> ApiConfig {
+    ApiConfig {
+        base_url: base_url.to_string(),
+        auth_token: None,
+        connect_timeout: Duration::from_secs(1),
+        request_timeout: Duration::from_secs(2),
+        max_retries: 2,
+        retry_base_delay: Duration::from_millis(1),
+        retry_max_delay: Duration::from_millis(10),
+        breaker_threshold: 5,
+        breaker_cooldown: Duration::from_secs(30),
+    }
+}
+
+// A 200 response with a JSON body
+pub fn json(body: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(body, "application/json")
+}
+
+// A handler for the mock backend with caching turned off and an empty in-memory database
+pub fn handler(mock: &MockStackUp) -> Handler {
+    let config = config(mock.config());
+    let cache = Arc::new(ApiCache::new(Arc::new(mock.client()), &config.cache));
+    let store: Arc<dyn Store> = Arc::new(SqliteStore::in_memory().unwrap());
+    Handler {
+        api: cache.clone(),
+        cache,
+        guild_settings: GuildSettingsCache::new(store.clone()),
+        cooldowns: Cooldowns::new(config.cooldowns.clone()),
+        store,
+        commands: CommandRegistry::default(),
+        config,
+    }
+}
+
+// A bot config using the given API config, with every cache TTL set to zero
+pub fn config(api: ApiConfig) -> Config {
+    Config {
+        discord_token: Secret::new("token"),
+        command_prefix: "!".to_string(),
+        prefix_commands: true,
+        guild_id: None,
+        allowed_guilds: Vec::new(),
+        owners: vec![UserId::new(OWNER_ID)],
+        database_file: PathBuf::from(":memory:"),
+        usage_retention: Duration::from_secs(90 * 24 * 60 * 60),
+        loading_threshold: Duration::from_secs(1),
+        log_level: LogLevel::Info,
+        log_format: LogFormat::Text,
+        api,
+        embed: EmbedConfig {
+            thumbnail_url: "https://example.com/thumbnail.png".to_string(),
+            page_size: 5,
+            page_timeout: Duration::from_secs(120),
+            colors: EmbedColors {
+                help: Colour::DARK_GREEN,
+                info: Colour::RED,
+                success: Colour::DARK_GREEN,
+                warning: Colour::ORANGE,
+                error: Colour::RED,
+                username: Colour::DARK_BLUE,
+                balance: Colour::DARK_PURPLE,
+                profile: Colour::DARK_GOLD,
+                campaigns: Colour::DARK_ORANGE,
+                pathways: Colour::DARK_TEAL,
+                hackathons: Colour::DARK_RED,
+                calendar: Colour::BLUE,
+            },
+        },
+        cache: CacheConfig {
+            stale_while_revalidate: Duration::ZERO,
+            campaigns_ttl: Duration::ZERO,
+            pathways_ttl: Duration::ZERO,
+            hackathons_ttl: Duration::ZERO,
+            balance_ttl: Duration::ZERO,
+            progress_ttl: Duration::ZERO,
+            user_details_ttl: Duration::ZERO,
+        },
+        announcements: AnnouncementConfig {
+            channels: Vec::new(),
+            poll_interval: Duration::from_secs(600),
+        },
+        cooldowns: CooldownConfig {
+            user: Rate {
+                uses: 5,
+                per: Duration::from_secs(20),
+            },
+            channel: Rate {
+                uses: 15,
+                per: Duration::from_secs(30),
+            },
+            commands: HashMap::from([(
+                "hackathons".to_string(),
+                Rate {
+                    uses: 2,
+                    per: Duration::from_secs(30),
+                },
+            )]),
+        },
+    }
+}
+
+// A caller with the given Discord user ID and guild permissions
+pub fn caller(id: u64, permissions: Permissions) -> Caller {
+    Caller {
+        id: UserId::new(id),
+        guild_id: None,
+        channel_id: ChannelId::new(1),
+        permissions,
+        is_owner: id == OWNER_ID,
+        correlation_id: 7,
+        prefix: "!".to_string(),
+        colors: config(api_config("http://localhost")).embed.colors,
+    }
+}
+
+// Run a prefix command the way the dispatcher does, e.g. `run(&handler, &caller, "link 42")`
+pub async fn run(handler: &Handler, caller: &Caller, content: &str) -> Response {
+    let (name, rest) = content.split_once(' ').unwrap_or((content, ""));
+    let command = handler.commands.find(name).expect("unknown command");
+    let args = CommandArgs::Prefix(parse_args(rest, command.switches()).unwrap());
+    handler.respond(command, caller, &args).await
+}
+
+// How a response was sent by a `RecordingResponder`
+#[derive(Clone, Debug, PartialEq)]
+pub enum Sent {
+    // As a new message
+    Send(Response),
+    // In place of the pending message
+    Edit(Response),
+    // As a reply to the invoking message
+    Reply(Response),
+    // As a reaction to the invoking message
+    Reaction(char),
+    // To the caller's DMs
+    DirectMessage(Response),
+    // As pages with buttons, listing every page in order
+    Pages(Vec<Response>),
+}
+
+// A responder that records what would have been sent to Discord
+#[derive(Default)]
+pub struct RecordingResponder {
+    pub sent: Mutex<Vec<Sent>>,
+    // Whether the caller has closed their DMs, so direct messages fail
+    pub dms_closed: bool,
+}
+
+impl RecordingResponder {
+    pub fn sent(&self) -> Vec<Sent> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Responder for RecordingResponder {
+    async fn send(&self, response: &Response) -> serenity::Result<()> {
+        self.sent.lock().unwrap().push(Sent::Send(response.clone()));
+        Ok(())
+    }
+
+    async fn edit(&self, response: &Response) -> serenity::Result<()> {
+        self.sent.lock().unwrap().push(Sent::Edit(response.clone()));
+        Ok(())
+    }
+
+    async fn reply(&self, response: &Response) -> serenity::Result<()> {
+        self.sent
+            .lock()
+            .unwrap()
+            .push(Sent::Reply(response.clone()));
+        Ok(())
+    }
+
+    async fn react(&self, emoji: char) -> serenity::Result<()> {
+        self.sent.lock().unwrap().push(Sent::Reaction(emoji));
+        Ok(())
+    }
+
+    async fn direct_message(&self, response: &Response) -> serenity::Result<()> {
+        if self.dms_closed {
+            return Err(serenity::Error::Other("Cannot send messages to this user"));
+        }
+        self.sent
+            .lock()
+            .unwrap()
+            .push(Sent::DirectMessage(response.clone()));
+        Ok(())
+    }
+
+    async fn paginate(&self, mut paginator: Paginator, _timeout: Duration) -> serenity::Result<()> {
+        // Turn through every page, the way a member pressing "Next" would
+        let mut pages = vec![paginator.page().clone()];
+        while paginator.press(PageButton::Next) {
+            pages.push(paginator.page().clone());
+        }
+        self.sent.lock().unwrap().push(Sent::Pages(pages));
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+use stack_buddy::config::{Config, ConfigError};
+use std::env;
+use std::sync::Mutex;
+use std::time::Duration;
+
+// The environment is shared by every test in this file, so they take turns with it
+static ENV: Mutex<()> = Mutex::new(());
+
+fn load(toml: &str) -> Result<Config, ConfigError> {
+    Config::from_table(toml.parse().unwrap())
+}
+
+fn problems(toml: &str) -> Vec<String> {
+    match load(toml) {
+        Err(ConfigError::Invalid { problems }) => problems,
+        other => panic!("expected invalid keys, got {:?}", other),
+    }
+}
+
+#[test]
+fn reports_every_problem_at_once() {
+    let _env = ENV.lock().unwrap_or_else(|err| err.into_inner());
+
+    let problems = problems(
+        r#"
+        allowed_guilds = ["1", "guild"]
+        log_level = "loud"
+
+        [api]
+        base_url = "ftp://example.com"
+
+        [embed.colors]
+        help = "green"
+        "#,
+    );
+
+    assert_eq!(problems.len(), 5, "{:#?}", problems);
+    for key in [
+        "`discord_token` (DISCORD_TOKEN) is missing",
+        "`allowed_guilds` (ALLOWED_GUILDS)",
+        "`log_level` (LOG_LEVEL)",
+        "`api.base_url` (API_BASE_URL)",
+        "`embed.colors.help` (EMBED_COLORS_HELP)",
+    ] {
+        assert!(
+            problems.iter().any(|problem| problem.starts_with(key)),
+            "no problem for {} in {:#?}",
+            key,
+            problems
+        );
+    }
+}
+
+#[test]
+fn environment_variables_override_the_file() {
+    let _env = ENV.lock().unwrap_or_else(|err| err.into_inner());
+    env::set_var("API_BASE_URL", "https://env.example.com/api/");
+    env::set_var("EMBED_PAGE_TIMEOUT_SECS", "45");
+
+    let config = load(
+        r#"
+        discord_token = "token"
+
+        [api]
+        base_url = "https://file.example.com/api"
+        max_retries = 7
+
+        [embed]
+        page_timeout_secs = 10
+        "#,
+    );
+    env::remove_var("API_BASE_URL");
+    env::remove_var("EMBED_PAGE_TIMEOUT_SECS");
+
+    let config = config.unwrap();
+    assert_eq!(config.api.base_url, "https://env.example.com/api");
+    assert_eq!(config.embed.page_timeout, Duration::from_secs(45));
+    // Keys without an environment variable still come from the file
+    assert_eq!(config.api.max_retries, 7);
+}
+
+#[test]
+fn reads_lists_from_arrays_and_comma_separated_values() {
+    let _env = ENV.lock().unwrap_or_else(|err| err.into_inner());
+    env::set_var("ANNOUNCEMENTS_CHANNELS", " 10, 20,,30 ");
+
+    let config = load(
+        r#"
+        discord_token = "token"
+        allowed_guilds = [1, "2"]
+        owner_ids = ["5"]
+
+        [cooldowns]
+        commands = ["balance=1/10", "Hackathons = 3/60"]
+        "#,
+    );
+    env::remove_var("ANNOUNCEMENTS_CHANNELS");
+
+    let config = config.unwrap();
+    let guilds: Vec<u64> = config.allowed_guilds.iter().map(|id| id.get()).collect();
+    assert_eq!(guilds, [1, 2]);
+    let owners: Vec<u64> = config.owners.iter().map(|id| id.get()).collect();
+    assert_eq!(owners, [5]);
+    let channels: Vec<u64> = config
+        .announcements
+        .channels
+        .iter()
+        .map(|id| id.get())
+        .collect();
+    assert_eq!(channels, [10, 20, 30]);
+
+    let commands = &config.cooldowns.commands;
+    assert_eq!(commands.len(), 2);
+    assert_eq!(commands["balance"].uses, 1);
+    assert_eq!(commands["balance"].per, Duration::from_secs(10));
+    assert_eq!(commands["hackathons"].uses, 3);
+    assert_eq!(commands["hackathons"].per, Duration::from_secs(60));
+}
+
+#[test]
+fn keeps_the_page_size_within_what_discord_allows() {
+    let _env = ENV.lock().unwrap_or_else(|err| err.into_inner());
+
+    for size in [0, 26] {
+        let problems = problems(&format!(
+            "discord_token = \"token\"\n[embed]\npage_size = {}",
+            size
+        ));
+        assert_eq!(
+            problems,
+            [format!(
+                "`embed.page_size` (EMBED_PAGE_SIZE) should be a number from 1 to 25, found `{}`",
+                size
+            )]
+        );
+    }
+
+    for size in [1, 25] {
+        let config = load(&format!(
+            "discord_token = \"token\"\n[embed]\npage_size = {}",
+            size
+        ))
+        .unwrap();
+        assert_eq!(config.embed.page_size, size);
+    }
+}
+
+#[test]
+fn never_shows_tokens() {
+    let _env = ENV.lock().unwrap_or_else(|err| err.into_inner());
+
+    let config = load(
+        r#"
+        discord_token = "discord-secret"
+
+        [api]
+        auth_token = "stackup-secret"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.discord_token.expose(), "discord-secret");
+    let debug = format!("{:?}", config);
+    assert!(!debug.contains("discord-secret"), "{}", debug);
+    assert!(!debug.contains("stackup-secret"), "{}", debug);
+
+    // Not even when they are malformed
+    let problems = problems(
+        r#"
+        discord_token = "discord-secret"
+
+        [api]
+        auth_token = "stackup secret"
+        "#,
+    );
+    assert_eq!(problems.len(), 1);
+    assert!(!problems[0].contains("stackup secret"), "{}", problems[0]);
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use common::*;
+use serenity::model::id::{ChannelId, GuildId, UserId};
+use serenity::model::permissions::Permissions;
+use stack_buddy::cooldowns::{Cooldown, Cooldowns};
+use std::time::{Duration, Instant};
+
+fn cooldowns() -> Cooldowns {
+    Cooldowns::new(config(api_config("http://localhost")).cooldowns)
+}
+
+#[test]
+fn notifies_once_then_drops_spam_until_the_bucket_refills() {
+    let cooldowns = cooldowns();
+    let (user, channel) = (UserId::new(1), ChannelId::new(10));
+    let start = Instant::now();
+
+    // Two uses of `hackathons` are allowed in a burst, then one more every 15 seconds
+    for _ in 0..2 {
+        assert_eq!(
+            cooldowns.check_at("hackathons", user, channel, start),
+            Cooldown::Ready
+        );
+    }
+    assert_eq!(
+        cooldowns.check_at("hackathons", user, channel, start),
+        Cooldown::Notify(Duration::from_secs(15))
+    );
+    let later = start + Duration::from_secs(5);
+    assert_eq!(
+        cooldowns.check_at("hackathons", user, channel, later),
+        Cooldown::Silent
+    );
+
+    // Other commands and other members have their own buckets
+    assert_eq!(
+        cooldowns.check_at("campaigns", user, channel, later),
+        Cooldown::Ready
+    );
+    assert_eq!(
+        cooldowns.check_at("hackathons", UserId::new(2), channel, later),
+        Cooldown::Ready
+    );
+
+    // Once a use has come back the member can go again, and is told again if they overdo it
+    let refilled = start + Duration::from_secs(15);
+    assert_eq!(
+        cooldowns.check_at("hackathons", user, channel, refilled),
+        Cooldown::Ready
+    );
+    assert!(matches!(
+        cooldowns.check_at("hackathons", user, channel, refilled),
+        Cooldown::Notify(_)
+    ));
+}
+
+#[test]
+fn limits_a_busy_channel_across_members() {
+    let cooldowns = cooldowns();
+    let channel = ChannelId::new(10);
+    let start = Instant::now();
+
+    // 15 uses of a command are allowed in a channel, however many members share them
+    for id in 1..=15 {
+        assert_eq!(
+            cooldowns.check_at("help", UserId::new(id), channel, start),
+            Cooldown::Ready
+        );
+    }
+    assert_eq!(
+        cooldowns.check_at("help", UserId::new(16), channel, start),
+        Cooldown::Notify(Duration::from_secs(2))
+    );
+    assert_eq!(
+        cooldowns.check_at("help", UserId::new(16), ChannelId::new(11), start),
+        Cooldown::Ready
+    );
+}
+
+#[tokio::test]
+async fn refused_commands_dont_use_up_cooldowns() {
+    let mock = MockStackUp::start().await;
+    let handler = handler(&mock);
+    let guild = Some(GuildId::new(9));
+    let channel = ChannelId::new(10);
+    let admin = handler
+        .caller(UserId::new(1), guild, channel, Permissions::MANAGE_GUILD, 7)
+        .await;
+    run(&handler, &admin, "config set disabled-commands hackathons").await;
+    let member = handler
+        .caller(UserId::new(2), guild, channel, Permissions::empty(), 7)
+        .await;
+
+    // Disabled commands and ones the member lacks the permissions for are refused every time
+    // instead of being cooled down
+    for name in ["hackathons", "config"] {
+        let command = handler.commands.find(name).unwrap();
+        for _ in 0..10 {
+            assert_eq!(handler.cooldown(command, &member).await, Cooldown::Ready);
+        }
+    }
+
+    // So the member still has every use of the commands they can run
+    let command = handler.commands.find("balance").unwrap();
+    for _ in 0..5 {
+        assert_eq!(handler.cooldown(command, &member).await, Cooldown::Ready);
+    }
+    assert!(matches!(
+        handler.cooldown(command, &member).await,
+        Cooldown::Notify(_)
+    ));
+}
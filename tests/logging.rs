@@ -0,0 +1,48 @@
+mod common;
+
+use common::*;
+use serenity::model::id::{ChannelId, UserId};
+use serenity::model::permissions::Permissions;
+use stack_buddy::logging::command_span;
+use std::sync::{Arc, Mutex};
+use tracing::span::{Attributes, Id};
+use tracing::{Instrument, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{Layer, Registry};
+
+// Records every span opened as its name, preceded by its parent's, like `parent > child`
+#[derive(Clone, Default)]
+struct SpanTree(Arc<Mutex<Vec<String>>>);
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for SpanTree {
+    fn on_new_span(&self, _attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let span = ctx.span(id).unwrap();
+        let path = match span.parent() {
+            Some(parent) => format!("{} > {}", parent.name(), span.name()),
+            None => span.name().to_string(),
+        };
+        self.0.lock().unwrap().push(path);
+    }
+}
+
+#[tokio::test]
+async fn nests_stackup_requests_under_the_command_span() {
+    let spans = SpanTree::default();
+    let _guard = tracing::subscriber::set_default(Registry::default().with(spans.clone()));
+    let mock = MockStackUp::start().await;
+    let handler = handler(&mock);
+
+    let span = command_span("campaigns", UserId::new(1), None, ChannelId::new(1), 7);
+    run(&handler, &caller(1, Permissions::empty()), "campaigns")
+        .instrument(span)
+        .await;
+
+    let spans = spans.0.lock().unwrap();
+    assert!(spans.contains(&"command".to_string()), "{:?}", spans);
+    assert!(
+        spans.contains(&"command > stackup_request".to_string()),
+        "{:?}",
+        spans
+    );
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use stack_buddy::models::*;
+use std::fmt::Debug;
+
+// Decode a fixture, encode it again and check decoding that gives back the same value
+fn round_trip<T>(fixture: &str) -> T
+where
+    T: DeserializeOwned + Serialize + PartialEq + Debug,
+{
+    let decoded: T = serde_json::from_str(fixture).unwrap();
+    let encoded = serde_json::to_string(&decoded).unwrap();
+    let decoded_again: T = serde_json::from_str(&encoded).unwrap();
+    assert_eq!(decoded, decoded_again, "{}", encoded);
+    decoded
+}
+
+#[test]
+fn round_trips_user_details() {
+    let user: GetUserResponse = round_trip(USER_JSON);
+    assert_eq!(user.username, "stackie42");
+    assert_eq!(user.career_level, CareerLevel::Intermediate);
+    assert_eq!(user.role, Role::Developer);
+    assert_eq!(user.tech_stack, ["Rust", "TypeScript"]);
+}
+
+#[test]
+fn round_trips_user_balance() {
+    let balance: UserBalance = round_trip(BALANCE_JSON);
+    assert_eq!(balance.user_id, "42");
+    assert_eq!(balance.current_balance, Money::usd(25000));
+    assert_eq!(balance.total_earnings, Money::usd(35050));
+    assert_eq!(balance.total_withdrawn, Money::usd(10050));
+    assert_eq!(balance.withdrawal_methods, ["paypal", "bank transfer"]);
+    assert_eq!(
+        balance.created_at,
+        Some(Utc.with_ymd_and_hms(2024, 1, 5, 10, 0, 0).unwrap())
+    );
+    // Timestamps without a timezone are in UTC
+    assert_eq!(
+        balance.updated_at,
+        Some(Utc.with_ymd_and_hms(2024, 6, 1, 12, 30, 0).unwrap())
+    );
+}
+
+#[test]
+fn round_trips_user_progress() {
+    let progress: UserProgress = round_trip(PROGRESS_JSON);
+    assert_eq!(progress.submissions, 12);
+    assert_eq!(progress.total_quest_earnings, Money::usd(35000));
+}
+
+#[test]
+fn round_trips_featured_lists() {
+    let campaigns: Vec<Campaigns> = round_trip(CAMPAIGNS_JSON);
+    assert_eq!(
+        campaigns[0].url.as_deref(),
+        Some("https://stackup.dev/campaigns/rust-rising")
+    );
+    assert_eq!(
+        campaigns[0].end_date,
+        Some(Utc.with_ymd_and_hms(2024, 7, 1, 0, 0, 0).unwrap())
+    );
+    // Campaigns without dates or links still decode
+    assert_eq!(campaigns[1].url, None);
+    assert_eq!(campaigns[1].start_date, None);
+
+    let pathways: Vec<Pathways> = round_trip(PATHWAYS_JSON);
+    assert_eq!(pathways[0].modules, 6);
+
+    let hackathons: Vec<Hackathons> = round_trip(HACKATHONS_JSON);
+    assert_eq!(hackathons[0].price, Money::usd(500000));
+    assert_eq!(hackathons[0].location, "Online");
+    assert_eq!(hackathons[0].end_date, None);
+}
+
+#[test]
+fn tolerates_missing_and_unexpected_fields() {
+    let user: GetUserResponse =
+        serde_json::from_str(r#"{"id": 1, "username": "new", "badges": []}"#).unwrap();
+    assert_eq!(user.username, "new");
+    assert_eq!(user.career_level, CareerLevel::Unknown);
+    assert!(user.tech_stack.is_empty());
+
+    // Numbers where strings are expected, and arrays where lists are usually strings
+    let balance: UserBalance = serde_json::from_str(
+        r#"{"user_id": 42, "withdrawal_methods": ["paypal"], "current_balance": null}"#,
+    )
+    .unwrap_or_else(|err| panic!("{}", err));
+    assert_eq!(balance.user_id, "42");
+    assert_eq!(balance.withdrawal_methods, ["paypal"]);
+
+    let hackathon: Hackathons = serde_json::from_str(r#"{"location": 3}"#).unwrap();
+    assert_eq!(hackathon.location, "3");
+}
+
+#[test]
+fn keeps_unknown_enum_values() {
+    let user: GetUserResponse =
+        serde_json::from_str(r#"{"career_level": "senior", "role": "data scientist"}"#).unwrap();
+    assert_eq!(user.career_level, CareerLevel::Other("senior".to_string()));
+    assert_eq!(user.career_level.to_string(), "senior");
+    assert_eq!(user.role, Role::DataScientist);
+    assert_eq!(user.role.to_string(), "Data Scientist");
+}
+
+#[test]
+fn parses_and_formats_money() {
+    assert_eq!("12".parse(), Ok(Money::usd(1200)));
+    assert_eq!("12.5".parse(), Ok(Money::usd(1250)));
+    assert_eq!("-0.99".parse(), Ok(Money::usd(-99)));
+    assert_eq!(".5".parse(), Ok(Money::usd(50)));
+    for invalid in ["", ".", "1.234", "12,50", "$12", "1e3"] {
+        assert!(invalid.parse::<Money>().is_err(), "{:?}", invalid);
+    }
+
+    assert_eq!(Money::usd(25000).to_string(), "$250");
+    assert_eq!(Money::usd(1250).to_string(), "$12.50");
+    assert_eq!(Money::usd(-99).to_string(), "-$0.99");
+
+    let amounts: Vec<Money> = serde_json::from_str(r#"[3, 3.1, "3.10", 0.07]"#).unwrap();
+    assert_eq!(
+        amounts,
+        [
+            Money::usd(300),
+            Money::usd(310),
+            Money::usd(310),
+            Money::usd(7)
+        ]
+    );
+    assert_eq!(
+        serde_json::to_string(&Money::usd(-1205)).unwrap(),
+        "\"-12.05\""
+    );
+}
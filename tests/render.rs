@@ -0,0 +1,187 @@
+mod common;
+
+use common::*;
+use serenity::model::colour::Colour;
+use stack_buddy::api::StackUpApi;
+use stack_buddy::config::EmbedColors;
+use stack_buddy::discord::render;
+use stack_buddy::discord::response::{Field, Response};
+
+fn colors() -> EmbedColors {
+    config(api_config("http://localhost")).embed.colors
+}
+
+// The fields of a response as (name, value) pairs
+fn fields(response: &Response) -> Vec<(&str, &str)> {
+    response
+        .fields
+        .iter()
+        .map(|field| (field.name.as_str(), field.value.as_str()))
+        .collect()
+}
+
+#[tokio::test]
+async fn renders_user_details() {
+    let mock = MockStackUp::start().await;
+    let user = mock.client().get_user_details(USER_ID).await.unwrap();
+
+    let response = render::user_details(&user, &colors());
+    assert_eq!(response.title.as_deref(), Some("stackie42"));
+    assert_eq!(
+        fields(&response),
+        [
+            ("Nationality", "Nigeria"),
+            ("Career Level", "Intermediate"),
+            ("Role", "Developer"),
+            ("Tech Stacks", "Rust, TypeScript"),
+        ]
+    );
+    assert_eq!(response.footer.as_deref(), Some("StackUp user ID 42"));
+}
+
+#[tokio::test]
+async fn renders_balance() {
+    let mock = MockStackUp::start().await;
+    let balance = mock.client().get_user_balance(USER_ID).await.unwrap();
+
+    let response = render::balance("<@1>'s", &balance, &colors());
+    assert_eq!(response.description, "<@1>'s StackUp balance");
+    assert_eq!(
+        fields(&response),
+        [
+            ("Current Balance", "$250"),
+            ("Lifetime Earnings", "$350.50"),
+            ("Withdrawn", "$100.50"),
+            ("Withdrawal Methods", "paypal, bank transfer"),
+            ("Last Updated", "<t:1717245000:R>"),
+        ]
+    );
+    assert!(response.fields[0].inline);
+    assert!(!response.direct);
+}
+
+#[tokio::test]
+async fn renders_progress_with_and_without_earnings() {
+    let mock = MockStackUp::start().await;
+    let progress = mock.client().get_user_progress(USER_ID).await.unwrap();
+
+    let response = render::progress("Your", &progress, true, &colors());
+    assert_eq!(response.description, "Your StackUp profile");
+    assert_eq!(
+        fields(&response),
+        [
+            ("Submissions", "12"),
+            ("Submitted", "10"),
+            ("Rewarded", "8"),
+            ("Total Earnings", "$350"),
+        ]
+    );
+
+    let response = render::progress("Your", &progress, false, &colors());
+    assert_eq!(response.fields[3].value, "*Private*");
+}
+
+#[tokio::test]
+async fn renders_featured_lists_as_fields() {
+    let mock = MockStackUp::start().await;
+    let client = mock.client();
+
+    let campaigns = render::campaigns(&client.get_featured_campaigns().await.unwrap(), &colors());
+    assert_eq!(campaigns.title.as_deref(), Some("Featured campaigns"));
+    assert_eq!(
+        campaigns.url.as_deref(),
+        Some("https://stackup.dev/campaigns")
+    );
+    assert_eq!(campaigns.color, colors().campaigns);
+    assert_eq!(campaigns.footer.as_deref(), Some("2 in total"));
+    let titles: Vec<&str> = campaigns
+        .fields
+        .iter()
+        .map(|field| field.name.as_str())
+        .collect();
+    assert_eq!(titles, ["Rust Rising", "Web3 Wonders"]);
+    assert!(
+        campaigns.fields[0].value.contains("**Quest Count:** 5"),
+        "{:?}",
+        campaigns
+    );
+
+    let pathways = render::pathways(&client.get_pathways().await.unwrap(), &colors());
+    assert_eq!(pathways.fields[0].name, "Backend Foundations");
+    assert!(
+        pathways.fields[0].value.contains("**Modules:** 6"),
+        "{:?}",
+        pathways
+    );
+
+    let hackathons = render::hackathons(&client.get_hackathons().await.unwrap(), &colors());
+    assert_eq!(hackathons.fields[0].name, "StackUp Summer Hack");
+    assert!(
+        hackathons.fields[0].value.contains("**Price:** $5000"),
+        "{:?}",
+        hackathons
+    );
+}
+
+#[test]
+fn renders_empty_featured_lists() {
+    let colors = colors();
+    assert_eq!(
+        render::campaigns(&[], &colors).description,
+        "There are no campaigns right now."
+    );
+    assert_eq!(
+        render::pathways(&[], &colors).description,
+        "There are no pathways right now."
+    );
+    assert_eq!(
+        render::hackathons(&[], &colors).description,
+        "There are no hackathons right now."
+    );
+}
+
+#[test]
+fn truncates_text_past_discords_limits() {
+    let response = Response::new("d".repeat(5000), Colour::BLUE)
+        .title("t".repeat(300))
+        .fields([Field::new("n".repeat(300), "v".repeat(2000), false)])
+        .footer("f".repeat(3000))
+        .truncated();
+
+    let title = response.title.unwrap();
+    assert_eq!(title.chars().count(), 256);
+    assert!(title.ends_with('…'));
+    assert_eq!(response.fields[0].name.chars().count(), 256);
+    assert_eq!(response.fields[0].value.chars().count(), 1024);
+    assert_eq!(response.footer.unwrap().chars().count(), 2048);
+
+    // The description gives way so the whole embed stays within 6000 characters
+    let total = "Stack-Buddy".len() + 256 + 256 + 1024 + 2048;
+    assert_eq!(response.description.chars().count(), 6000 - total);
+}
+
+#[test]
+fn drops_fields_that_dont_fit() {
+    let fields = (0..30).map(|n| Field::new(format!("Field {}", n), "v".repeat(1000), false));
+    let response = Response::new("d".repeat(100), Colour::BLUE)
+        .fields(fields)
+        .truncated();
+
+    // The description is dropped first, and only five of the long fields fit in 6000 characters, well under the limit of 25
+    assert_eq!(response.fields.len(), 5);
+    assert_eq!(response.fields[4].name, "Field 4");
+    assert_eq!(response.description, "");
+
+    let short = (0..30).map(|n| Field::new(n.to_string(), "v", true));
+    let response = Response::new("", Colour::BLUE).fields(short).truncated();
+    assert_eq!(response.fields.len(), 25);
+}
+
+#[test]
+fn leaves_responses_that_fit_alone() {
+    let response = Response::new("Hello", Colour::BLUE)
+        .title("Greeting")
+        .fields([Field::new("Name", "Value", true)])
+        .footer("Bye");
+    assert_eq!(response.truncated(), response);
+}
@@ -0,0 +1,134 @@
+use chrono::Utc;
+use serenity::model::id::{GuildId, UserId};
+use stack_buddy::store::{CommandUsage, SqliteStore, Store};
+use stack_buddy::subscriptions::Topic;
+use std::collections::{HashMap, HashSet};
+
+fn store() -> SqliteStore {
+    SqliteStore::in_memory().unwrap()
+}
+
+// A database path no other test uses, which doesn't exist yet
+fn temp_path(name: &str) -> std::path::PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("stack-buddy-store-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[tokio::test]
+async fn links_accounts_and_keeps_their_privacy_setting() {
+    let store = store();
+    let user = UserId::new(1);
+    assert!(store.account_link(user).await.unwrap().is_none());
+    assert!(!store.set_private(user, false).await.unwrap());
+
+    // Balances are private until the owner opts out, and relinking keeps that choice
+    store.link(user, 42).await.unwrap();
+    assert!(store.account_link(user).await.unwrap().unwrap().private);
+    assert!(store.set_private(user, false).await.unwrap());
+    store.link(user, 43).await.unwrap();
+    let link = store.account_link(user).await.unwrap().unwrap();
+    assert_eq!((link.stackup_id, link.private), (43, false));
+
+    assert_eq!(store.unlink(user).await.unwrap(), Some(43));
+    assert_eq!(store.unlink(user).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn keeps_guild_settings_and_seen_items() {
+    let store = store();
+    let guild = GuildId::new(7);
+    store.set_guild_setting(guild, "prefix", "?").await.unwrap();
+    store.set_guild_setting(guild, "prefix", "$").await.unwrap();
+    assert_eq!(
+        store.guild_settings(guild).await.unwrap(),
+        HashMap::from([("prefix".to_string(), "$".to_string())])
+    );
+    assert!(store.remove_guild_setting(guild, "prefix").await.unwrap());
+    assert!(!store.remove_guild_setting(guild, "prefix").await.unwrap());
+
+    // A feed that was polled while empty is different from one that was never polled
+    assert_eq!(store.seen_items(Topic::Campaigns).await.unwrap(), None);
+    store
+        .save_seen_items(Topic::Campaigns, &HashSet::new())
+        .await
+        .unwrap();
+    assert_eq!(
+        store.seen_items(Topic::Campaigns).await.unwrap(),
+        Some(HashSet::new())
+    );
+    store
+        .save_seen_items(Topic::Campaigns, &HashSet::from([1, 2]))
+        .await
+        .unwrap();
+    assert_eq!(
+        store.seen_items(Topic::Campaigns).await.unwrap(),
+        Some(HashSet::from([1, 2]))
+    );
+}
+
+#[tokio::test]
+async fn counts_command_usage() {
+    let store = store();
+    for command in ["balance", "help", "balance"] {
+        store
+            .record_command(command, UserId::new(1), Some(GuildId::new(7)))
+            .await
+            .unwrap();
+    }
+    store
+        .record_command("help", UserId::new(2), None)
+        .await
+        .unwrap();
+    store
+        .record_command("balance", UserId::new(2), None)
+        .await
+        .unwrap();
+
+    let usage = |command: &str, uses| CommandUsage {
+        command: command.to_string(),
+        uses,
+    };
+    assert_eq!(
+        store.command_usage().await.unwrap(),
+        [usage("balance", 3), usage("help", 2)]
+    );
+}
+
+#[tokio::test]
+async fn reopening_the_database_keeps_its_contents() {
+    let path = temp_path("reopen.db");
+    let store = SqliteStore::open(&path).unwrap();
+    store
+        .subscribe(UserId::new(1), Topic::Hackathons)
+        .await
+        .unwrap();
+    drop(store);
+
+    // The migrations that already ran aren't run again
+    let store = SqliteStore::open(&path).unwrap();
+    assert_eq!(
+        store.topics(UserId::new(1)).await.unwrap(),
+        [Topic::Hackathons]
+    );
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn forgets_old_command_usage() {
+    let store = store();
+    store
+        .record_command("help", UserId::new(1), None)
+        .await
+        .unwrap();
+
+    // Uses from after the cutoff are kept
+    let yesterday = Utc::now() - chrono::Duration::days(1);
+    assert_eq!(store.prune_command_usage(yesterday).await.unwrap(), 0);
+    assert_eq!(store.command_usage().await.unwrap().len(), 1);
+
+    let tomorrow = Utc::now() + chrono::Duration::days(1);
+    assert_eq!(store.prune_command_usage(tomorrow).await.unwrap(), 1);
+    assert!(store.command_usage().await.unwrap().is_empty());
+}